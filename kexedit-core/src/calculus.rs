@@ -0,0 +1,291 @@
+use crate::keyframe::{evaluate, evaluate_segment, InterpolationType, Keyframe};
+
+/// Subintervals per segment for Simpson integration of curved segments. Must be even.
+const SIMPSON_STEPS: usize = 64;
+/// Samples per segment when searching curved segments for extrema.
+const EXTREMA_SAMPLES: usize = 64;
+const REFINE_ITERATIONS: usize = 24;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct CurveExtrema {
+    pub min: f32,
+    pub min_time: f32,
+    pub max: f32,
+    pub max_time: f32,
+}
+
+/// Shape of the curve between two times, after clamping to the first and last key.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Piece {
+    Flat(f32),
+    Linear { start: Keyframe, end: Keyframe },
+    Curved { start: Keyframe, end: Keyframe },
+}
+
+impl Piece {
+    fn new(start: &Keyframe, end: &Keyframe) -> Self {
+        if matches!(start.out_interpolation, InterpolationType::Constant) {
+            return Self::Flat(start.value);
+        }
+        if matches!(end.in_interpolation, InterpolationType::Constant) {
+            return Self::Flat(end.value);
+        }
+        let linear = matches!(start.out_interpolation, InterpolationType::Linear)
+            && matches!(end.in_interpolation, InterpolationType::Linear);
+        if linear {
+            Self::Linear { start: *start, end: *end }
+        } else {
+            Self::Curved { start: *start, end: *end }
+        }
+    }
+
+    fn value(&self, t: f32) -> f32 {
+        match self {
+            Self::Flat(value) => *value,
+            Self::Linear { start, end } | Self::Curved { start, end } => evaluate_segment(start, end, t),
+        }
+    }
+}
+
+/// Splits `[from, to]` (with `from <= to`) into pieces of uniform shape.
+fn pieces(keyframes: &[Keyframe], from: f32, to: f32) -> Vec<(f32, f32, Piece)> {
+    let first = &keyframes[0];
+    let last = &keyframes[keyframes.len() - 1];
+    let mut result = Vec::new();
+
+    if from < first.time {
+        result.push((from, to.min(first.time), Piece::Flat(first.value)));
+    }
+    for pair in keyframes.windows(2) {
+        let (a, b) = (pair[0].time.max(from), pair[1].time.min(to));
+        if b > a {
+            result.push((a, b, Piece::new(&pair[0], &pair[1])));
+        }
+    }
+    if to > last.time {
+        result.push((from.max(last.time), to, Piece::Flat(last.value)));
+    }
+    result
+}
+
+/// Definite integral of the curve from `from` to `to`, e.g. the total roll of
+/// a roll-speed curve over a section.
+///
+/// Exact for constant and linear segments; Bezier and easing segments use
+/// Simpson's rule. Outside the keys the curve holds the first or last value,
+/// and an empty curve is `default_value` everywhere. Swapping the bounds
+/// negates the result.
+pub fn integrate(keyframes: &[Keyframe], from: f32, to: f32, default_value: f32) -> f32 {
+    if to < from {
+        return -integrate(keyframes, to, from, default_value);
+    }
+    if keyframes.is_empty() {
+        return default_value * (to - from);
+    }
+
+    pieces(keyframes, from, to)
+        .into_iter()
+        .map(|(a, b, piece)| match piece {
+            Piece::Flat(value) => value * (b - a),
+            Piece::Linear { .. } => 0.5 * (piece.value(a) + piece.value(b)) * (b - a),
+            Piece::Curved { .. } => {
+                let h = (b - a) / SIMPSON_STEPS as f32;
+                let sum: f32 = (1..SIMPSON_STEPS)
+                    .map(|i| {
+                        let weight = if i % 2 == 1 { 4.0 } else { 2.0 };
+                        weight * piece.value(a + i as f32 * h)
+                    })
+                    .sum();
+                (piece.value(a) + sum + piece.value(b)) * h / 3.0
+            }
+        })
+        .sum()
+}
+
+/// Slope of the curve at `t`, using the segment that `evaluate` uses at `t`.
+///
+/// Exact for constant and linear segments; Bezier and easing segments use a
+/// central difference kept inside the segment. Zero outside the keys.
+pub fn derivative(keyframes: &[Keyframe], t: f32) -> f32 {
+    if keyframes.len() < 2 || t < keyframes[0].time || t >= keyframes[keyframes.len() - 1].time {
+        return 0.0;
+    }
+
+    let i = keyframes.partition_point(|kf| kf.time <= t).saturating_sub(1);
+    let (start, end) = (&keyframes[i], &keyframes[i + 1]);
+    let dt = end.time - start.time;
+    match Piece::new(start, end) {
+        Piece::Flat(_) => 0.0,
+        Piece::Linear { .. } => (end.value - start.value) / dt,
+        piece @ Piece::Curved { .. } => {
+            let h = 1e-3 * dt;
+            let a = (t - h).max(start.time);
+            let b = (t + h).min(end.time);
+            (piece.value(b) - piece.value(a)) / (b - a)
+        }
+    }
+}
+
+/// Smallest and largest value of the curve over `[from, to]`, with the times
+/// at which they occur.
+///
+/// Constant and linear segments are checked at their ends; Bezier and easing
+/// segments are sampled and the best samples refined by golden-section search.
+pub fn extrema(keyframes: &[Keyframe], from: f32, to: f32, default_value: f32) -> CurveExtrema {
+    let (from, to) = if to < from { (to, from) } else { (from, to) };
+    let start_value = evaluate(keyframes, from, default_value);
+    let mut result = CurveExtrema {
+        min: start_value,
+        min_time: from,
+        max: start_value,
+        max_time: from,
+    };
+    if keyframes.is_empty() {
+        return result;
+    }
+
+    let mut consider = |t: f32, value: f32| {
+        if value < result.min {
+            result.min = value;
+            result.min_time = t;
+        }
+        if value > result.max {
+            result.max = value;
+            result.max_time = t;
+        }
+    };
+
+    for (a, b, piece) in pieces(keyframes, from, to) {
+        consider(a, piece.value(a));
+        consider(b, piece.value(b));
+        if !matches!(piece, Piece::Curved { .. }) {
+            continue;
+        }
+
+        let h = (b - a) / EXTREMA_SAMPLES as f32;
+        let samples: Vec<f32> = (0..=EXTREMA_SAMPLES).map(|i| piece.value(a + i as f32 * h)).collect();
+        for i in 1..EXTREMA_SAMPLES {
+            let (prev, value, next) = (samples[i - 1], samples[i], samples[i + 1]);
+            let is_max = value >= prev && value >= next;
+            let is_min = value <= prev && value <= next;
+            if is_max || is_min {
+                let sign = if is_max { -1.0 } else { 1.0 };
+                let lo = a + (i - 1) as f32 * h;
+                let t = golden_section(|t| sign * piece.value(t), lo, lo + 2.0 * h);
+                consider(t, piece.value(t));
+            }
+        }
+    }
+
+    result
+}
+
+/// Minimiser of a unimodal `f` on `[lo, hi]`.
+fn golden_section(f: impl Fn(f32) -> f32, mut lo: f32, mut hi: f32) -> f32 {
+    let ratio = 0.5 * (5f32.sqrt() - 1.0);
+    let mut c = hi - ratio * (hi - lo);
+    let mut d = lo + ratio * (hi - lo);
+    for _ in 0..REFINE_ITERATIONS {
+        if f(c) < f(d) {
+            hi = d;
+        } else {
+            lo = c;
+        }
+        c = hi - ratio * (hi - lo);
+        d = lo + ratio * (hi - lo);
+    }
+    0.5 * (lo + hi)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn key(time: f32, value: f32, interpolation: InterpolationType) -> Keyframe {
+        Keyframe::new(time, value, interpolation, interpolation, 0.0, 0.0, 1.0 / 3.0, 1.0 / 3.0)
+    }
+
+    #[test]
+    fn integrate_linear_and_constant_exactly() {
+        let keys = [
+            key(0.0, 0.0, InterpolationType::Linear),
+            Keyframe::new(2.0, 4.0, InterpolationType::Linear, InterpolationType::Constant, 0.0, 0.0, 1.0 / 3.0, 1.0 / 3.0),
+            key(3.0, 1.0, InterpolationType::Linear),
+        ];
+
+        assert_relative_eq!(integrate(&keys, 0.0, 2.0, 0.0), 4.0);
+        assert_relative_eq!(integrate(&keys, 2.0, 3.0, 0.0), 4.0);
+        assert_relative_eq!(integrate(&keys, 0.0, 1.0, 0.0), 1.0);
+        assert_relative_eq!(integrate(&keys, 3.0, 5.0, 0.0), 2.0);
+        assert_relative_eq!(integrate(&keys, -1.0, 0.0, 0.0), 0.0);
+        assert_relative_eq!(integrate(&keys, 2.0, 0.0, 0.0), -4.0);
+    }
+
+    #[test]
+    fn integrate_empty_uses_default() {
+        assert_relative_eq!(integrate(&[], 1.0, 3.0, 0.5), 1.0);
+    }
+
+    #[test]
+    fn integrate_bezier_roll_speed_gives_total_rotation() {
+        let keys = [Keyframe::simple(0.0, 0.0), Keyframe::simple(2.0, std::f32::consts::PI)];
+
+        assert_relative_eq!(integrate(&keys, 0.0, 2.0, 0.0), std::f32::consts::PI, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn integrate_cosine_matches_closed_form() {
+        let keys = [
+            key(0.0, 0.0, InterpolationType::Cosine),
+            key(1.0, 2.0, InterpolationType::Cosine),
+        ];
+
+        assert_relative_eq!(integrate(&keys, 0.0, 1.0, 0.0), 1.0, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn derivative_of_linear_and_bezier() {
+        let linear = [key(0.0, 1.0, InterpolationType::Linear), key(2.0, 5.0, InterpolationType::Linear)];
+        assert_relative_eq!(derivative(&linear, 0.5), 2.0);
+        assert_relative_eq!(derivative(&linear, 3.0), 0.0);
+
+        let bezier = [
+            Keyframe::new(0.0, 0.0, InterpolationType::Bezier, InterpolationType::Bezier, 0.0, 3.0, 1.0 / 3.0, 1.0 / 3.0),
+            Keyframe::new(1.0, 1.0, InterpolationType::Bezier, InterpolationType::Bezier, 0.0, 0.0, 1.0 / 3.0, 1.0 / 3.0),
+        ];
+        assert_relative_eq!(derivative(&bezier, 0.0), 3.0, epsilon = 1e-2);
+        assert_relative_eq!(derivative(&bezier, 1.0 - 1e-4), 0.0, epsilon = 1e-2);
+    }
+
+    #[test]
+    fn extrema_finds_interior_bezier_peak() {
+        let keys = [
+            Keyframe::new(0.0, 0.0, InterpolationType::Bezier, InterpolationType::Bezier, 0.0, 4.0, 1.0 / 3.0, 1.0 / 3.0),
+            Keyframe::new(1.0, 0.0, InterpolationType::Bezier, InterpolationType::Bezier, -4.0, 0.0, 1.0 / 3.0, 1.0 / 3.0),
+        ];
+
+        let result = extrema(&keys, 0.0, 1.0, 0.0);
+
+        assert_relative_eq!(result.max, 1.0, epsilon = 1e-3);
+        assert_relative_eq!(result.max_time, 0.5, epsilon = 1e-2);
+        assert_relative_eq!(result.min, 0.0);
+    }
+
+    #[test]
+    fn extrema_of_linear_curve_within_range() {
+        let keys = [
+            key(0.0, 1.0, InterpolationType::Linear),
+            key(1.0, 3.0, InterpolationType::Linear),
+            key(2.0, -1.0, InterpolationType::Linear),
+        ];
+
+        let result = extrema(&keys, 0.5, 1.75, 0.0);
+
+        assert_relative_eq!(result.max, 3.0);
+        assert_relative_eq!(result.max_time, 1.0);
+        assert_relative_eq!(result.min, 0.0);
+        assert_relative_eq!(result.min_time, 1.75);
+    }
+}
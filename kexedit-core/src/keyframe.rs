@@ -1,9 +1,56 @@
+use std::f32::consts::PI;
+
 #[repr(C)]
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum InterpolationType {
     Constant,
     Linear,
     Bezier,
+    /// `(1 - cos πs) / 2`: zero slope at both keys.
+    Cosine,
+    /// `s - sin 2πs / 2π`: zero slope and curvature at both keys.
+    Sinusoidal,
+    /// `10s³ - 15s⁴ + 6s⁵`: C2-smooth smoothstep.
+    Quintic,
+    /// `(1 - e^(-15s³)) / (1 - e^-15)`: eases out of the first key and settles
+    /// on the second key's value by three quarters of the segment.
+    Plateau,
+}
+
+impl InterpolationType {
+    /// Eased fraction of the way from start to end value at segment fraction `s`,
+    /// or `None` for types that are not fixed easing curves.
+    fn ease(self, s: f32) -> Option<f32> {
+        match self {
+            Self::Cosine => Some(0.5 * (1.0 - (PI * s).cos())),
+            Self::Sinusoidal => Some(s - (2.0 * PI * s).sin() / (2.0 * PI)),
+            Self::Quintic => Some(s * s * s * (10.0 + s * (-15.0 + 6.0 * s))),
+            Self::Plateau => Some((1.0 - (-15.0 * s * s * s).exp()) / (1.0 - (-15.0f32).exp())),
+            Self::Constant | Self::Linear | Self::Bezier => None,
+        }
+    }
+}
+
+/// How the editor constrains a key's tangent handles.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HandleType {
+    /// Tangents and weights are set independently.
+    Free,
+    /// In and out tangents share a slope, set from whichever handle was last
+    /// edited; weights stay independent.
+    Aligned,
+    /// Tangents follow the neighbouring keys, flat at local extrema and ends.
+    Auto,
+    /// Each tangent points straight at the neighbouring key, like a linear segment.
+    Vector,
+}
+
+/// One of a key's two tangent handles.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HandleSide {
+    In,
+    Out,
 }
 
 #[repr(C)]
@@ -17,6 +64,7 @@ pub struct Keyframe {
     pub out_tangent: f32,
     pub in_weight: f32,
     pub out_weight: f32,
+    pub handle_type: HandleType,
 }
 
 impl Keyframe {
@@ -40,6 +88,23 @@ impl Keyframe {
             out_tangent,
             in_weight,
             out_weight,
+            handle_type: HandleType::Free,
+        }
+    }
+
+    pub const fn with_handle_type(self, handle_type: HandleType) -> Self {
+        Self { handle_type, ..self }
+    }
+
+    /// Sets the tangent on `side`, as when that handle is dragged. `Aligned`
+    /// keys mirror the slope to the other side; weights are left alone.
+    pub fn set_tangent(&mut self, side: HandleSide, tangent: f32) {
+        let mirror = self.handle_type == HandleType::Aligned;
+        if side == HandleSide::In || mirror {
+            self.in_tangent = tangent;
+        }
+        if side == HandleSide::Out || mirror {
+            self.out_tangent = tangent;
         }
     }
 
@@ -76,6 +141,58 @@ pub fn evaluate(keyframes: &[Keyframe], t: f32, default_value: f32) -> f32 {
     evaluate_segment(&start, &end, t)
 }
 
+/// Stateful `evaluate` for a simulation stepping forward through a curve.
+///
+/// The cursor remembers the segment of the last lookup and walks forward from
+/// it, so non-decreasing times cost amortised O(1) instead of a binary search.
+/// Stepping backwards falls back to the search. Results are identical to
+/// `evaluate`.
+#[derive(Debug, Clone)]
+pub struct KeyframeCursor<'a> {
+    keyframes: &'a [Keyframe],
+    index: usize,
+}
+
+impl<'a> KeyframeCursor<'a> {
+    pub const fn new(keyframes: &'a [Keyframe]) -> Self {
+        Self { keyframes, index: 0 }
+    }
+
+    pub fn evaluate(&mut self, t: f32, default_value: f32) -> f32 {
+        let keyframes = self.keyframes;
+        if keyframes.is_empty() {
+            return default_value;
+        }
+        if t <= keyframes[0].time {
+            return keyframes[0].value;
+        }
+
+        if t.is_nan() || keyframes[self.index].time > t {
+            self.index = keyframes.partition_point(|kf| kf.time <= t).saturating_sub(1);
+        } else {
+            while self.index + 1 < keyframes.len() && keyframes[self.index + 1].time <= t {
+                self.index += 1;
+            }
+        }
+
+        if self.index >= keyframes.len() - 1 {
+            return keyframes[keyframes.len() - 1].value;
+        }
+        evaluate_segment(&keyframes[self.index], &keyframes[self.index + 1], t)
+    }
+}
+
+/// Evaluates every time in `times` into the matching slot of `out`, which must
+/// be the same length. Sorted times are fastest; any order gives the same
+/// results as calling `evaluate` per time.
+pub fn evaluate_batch(keyframes: &[Keyframe], times: &[f32], default_value: f32, out: &mut [f32]) {
+    debug_assert_eq!(times.len(), out.len());
+    let mut cursor = KeyframeCursor::new(keyframes);
+    for (value, &t) in out.iter_mut().zip(times) {
+        *value = cursor.evaluate(t, default_value);
+    }
+}
+
 pub fn evaluate_segment(start: &Keyframe, end: &Keyframe, t: f32) -> f32 {
     if matches!(start.out_interpolation, InterpolationType::Constant) {
         return start.value;
@@ -83,6 +200,9 @@ pub fn evaluate_segment(start: &Keyframe, end: &Keyframe, t: f32) -> f32 {
     if matches!(end.in_interpolation, InterpolationType::Constant) {
         return end.value;
     }
+    if end.time <= start.time {
+        return end.value;
+    }
 
     let interpolation_type = get_max_interpolation(start.out_interpolation, end.in_interpolation);
 
@@ -93,6 +213,11 @@ pub fn evaluate_segment(start: &Keyframe, end: &Keyframe, t: f32) -> f32 {
         }
         InterpolationType::Bezier => evaluate_bezier_2d(start, end, t),
         InterpolationType::Constant => start.value,
+        easing => {
+            let segment_t = ((t - start.time) / (end.time - start.time)).clamp(0.0, 1.0);
+            let eased = easing.ease(segment_t).unwrap_or(segment_t);
+            start.value + (end.value - start.value) * eased
+        }
     }
 }
 
@@ -153,13 +278,252 @@ fn evaluate_bezier_2d(start: &Keyframe, end: &Keyframe, target_time: f32) -> f32
         + final_u3 * p3_y
 }
 
+/// Segment type when the two sides of a segment disagree. Bezier keeps its
+/// tangents over an easing on the other side, an easing beats linear, and two
+/// different easings take the start key's.
 fn get_max_interpolation(a: InterpolationType, b: InterpolationType) -> InterpolationType {
-    let a_val = a as i32;
-    let b_val = b as i32;
-    if a_val > b_val {
+    fn precedence(interpolation: InterpolationType) -> u8 {
+        match interpolation {
+            InterpolationType::Constant => 0,
+            InterpolationType::Linear => 1,
+            InterpolationType::Cosine
+            | InterpolationType::Sinusoidal
+            | InterpolationType::Quintic
+            | InterpolationType::Plateau => 2,
+            InterpolationType::Bezier => 3,
+        }
+    }
+
+    if precedence(b) > precedence(a) {
+        b
+    } else {
         a
+    }
+}
+
+fn slope(from: &Keyframe, to: &Keyframe) -> f32 {
+    let dt = to.time - from.time;
+    if dt.abs() < f32::EPSILON {
+        0.0
     } else {
-        b
+        (to.value - from.value) / dt
+    }
+}
+
+/// Recomputes tangents, and weights where the handle type fixes them, after
+/// keys have been moved, added or removed. `Free` keys are left untouched.
+///
+/// `Aligned` keys keep their slope, which stays collinear however the
+/// neighbours move since tangents are slopes rather than handle offsets;
+/// handle edits go through `Keyframe::set_tangent`. A key whose two slopes
+/// disagree, e.g. after loading, is re-aligned to its out slope, or its in
+/// slope if it is the last key. `Auto` keys take the slope between their neighbours, flattened at local
+/// extrema and at the first and last key so the curve does not overshoot, with
+/// weights of 1/3. `Vector` keys aim each tangent at the adjacent key with
+/// weights of 1/3.
+pub fn recompute_tangents(keyframes: &mut [Keyframe]) {
+    for i in 0..keyframes.len() {
+        let prev = i.checked_sub(1).map(|j| keyframes[j]);
+        let next = keyframes.get(i + 1).copied();
+        let key = &mut keyframes[i];
+
+        match key.handle_type {
+            HandleType::Free => {}
+            HandleType::Aligned => {
+                let tangent = if next.is_none() && prev.is_some() {
+                    key.in_tangent
+                } else {
+                    key.out_tangent
+                };
+                key.in_tangent = tangent;
+                key.out_tangent = tangent;
+            }
+            HandleType::Auto => {
+                let tangent = match (prev, next) {
+                    (Some(prev), Some(next)) => {
+                        let extremum = (key.value - prev.value) * (next.value - key.value) <= 0.0;
+                        if extremum {
+                            0.0
+                        } else {
+                            slope(&prev, &next)
+                        }
+                    }
+                    _ => 0.0,
+                };
+                key.in_tangent = tangent;
+                key.out_tangent = tangent;
+                key.in_weight = 1.0 / 3.0;
+                key.out_weight = 1.0 / 3.0;
+            }
+            HandleType::Vector => {
+                let current = *key;
+                key.in_tangent = prev.map_or(0.0, |prev| slope(&prev, &current));
+                key.out_tangent = next.map_or(0.0, |next| slope(&current, &next));
+                key.in_weight = 1.0 / 3.0;
+                key.out_weight = 1.0 / 3.0;
+            }
+        }
+    }
+}
+
+/// Keyframe with Bezier interpolation whose weights make the segment a cubic
+/// Hermite curve in time, so tangents are plain slopes.
+fn hermite_keyframe(time: f32, value: f32, in_tangent: f32, out_tangent: f32) -> Keyframe {
+    Keyframe::new(
+        time,
+        value,
+        InterpolationType::Bezier,
+        InterpolationType::Bezier,
+        in_tangent,
+        out_tangent,
+        1.0 / 3.0,
+        1.0 / 3.0,
+    )
+}
+
+/// Least-squares out tangent at `start` and in tangent at `end` for a Hermite
+/// segment through both endpoints, pulled slightly towards the chord slope so
+/// segments with few interior samples stay well-posed.
+fn fit_tangents(times: &[f32], values: &[f32], start: usize, end: usize) -> (f32, f32) {
+    let dt = times[end] - times[start];
+    let (y0, y1) = (values[start], values[end]);
+    let chord = if dt.abs() < f32::EPSILON { 0.0 } else { (y1 - y0) / dt };
+
+    let (mut a00, mut a01, mut a11, mut b0, mut b1) = (0.0f32, 0.0f32, 0.0f32, 0.0f32, 0.0f32);
+    for i in start + 1..end {
+        let s = (times[i] - times[start]) / dt;
+        let (s2, s3) = (s * s, s * s * s);
+        let h00 = 2.0 * s3 - 3.0 * s2 + 1.0;
+        let h01 = -2.0 * s3 + 3.0 * s2;
+        let h10 = (s3 - 2.0 * s2 + s) * dt;
+        let h11 = (s3 - s2) * dt;
+        let r = values[i] - h00 * y0 - h01 * y1;
+        a00 += h10 * h10;
+        a01 += h10 * h11;
+        a11 += h11 * h11;
+        b0 += h10 * r;
+        b1 += h11 * r;
+    }
+
+    let lambda = 1e-4 * (a00 + a11).max(dt * dt);
+    a00 += lambda;
+    a11 += lambda;
+    b0 += lambda * chord;
+    b1 += lambda * chord;
+
+    let det = a00 * a11 - a01 * a01;
+    if det.abs() < f32::MIN_POSITIVE {
+        return (chord, chord);
+    }
+    ((a11 * b0 - a01 * b1) / det, (a00 * b1 - a01 * b0) / det)
+}
+
+/// Fitted tangents for `[start, end]` if the segment stays within `tolerance`.
+fn fit_segment(times: &[f32], values: &[f32], start: usize, end: usize, tolerance: f32) -> Option<(f32, f32)> {
+    let (out_tangent, in_tangent) = fit_tangents(times, values, start, end);
+    let first = hermite_keyframe(times[start], values[start], 0.0, out_tangent);
+    let last = hermite_keyframe(times[end], values[end], in_tangent, 0.0);
+    let fits = (start + 1..end).all(|i| (evaluate_segment(&first, &last, times[i]) - values[i]).abs() <= tolerance);
+    fits.then_some((out_tangent, in_tangent))
+}
+
+/// Fits Bezier keyframes to a sampled signal so that `evaluate` stays within
+/// `tolerance` of every sample.
+///
+/// `times` must be increasing. Each segment is fitted with least-squares
+/// tangents and grown, by doubling then bisecting, to the furthest sample it
+/// still fits, so smooth signals need few keys. Keys sit on samples; their in
+/// and out tangents may differ.
+pub fn fit_keyframes(times: &[f32], values: &[f32], tolerance: f32) -> Vec<Keyframe> {
+    let count = times.len().min(values.len());
+    if count == 0 {
+        return Vec::new();
+    }
+
+    let mut result = vec![hermite_keyframe(times[0], values[0], 0.0, 0.0)];
+    let mut start = 0;
+    while start + 1 < count {
+        let last = count - 1;
+        let mut fitted = (start + 1, fit_segment(times, values, start, start + 1, tolerance).unwrap_or_default());
+        let mut step = 2;
+        let mut failed = None;
+        while fitted.0 < last {
+            let end = (start + step).min(last);
+            match fit_segment(times, values, start, end, tolerance) {
+                Some(tangents) => fitted = (end, tangents),
+                None => {
+                    failed = Some(end);
+                    break;
+                }
+            }
+            step *= 2;
+        }
+        if let Some(mut high) = failed {
+            while high - fitted.0 > 1 {
+                let mid = (fitted.0 + high) / 2;
+                match fit_segment(times, values, start, mid, tolerance) {
+                    Some(tangents) => fitted = (mid, tangents),
+                    None => high = mid,
+                }
+            }
+        }
+
+        let (end, (out_tangent, in_tangent)) = fitted;
+        if let Some(key) = result.last_mut() {
+            key.out_tangent = out_tangent;
+        }
+        result.push(hermite_keyframe(times[end], values[end], in_tangent, 0.0));
+        start = end;
+    }
+
+    result[0].in_tangent = result[0].out_tangent;
+    let last = result.len() - 1;
+    result[last].out_tangent = result[last].in_tangent;
+    result
+}
+
+const SIMPLIFY_SAMPLES: usize = 16;
+
+/// Largest difference between `original` and `candidate` over `[start, end]`,
+/// checked at `SIMPLIFY_SAMPLES` points per original segment.
+fn max_deviation(original: &[Keyframe], candidate: &[Keyframe], start: f32, end: f32) -> f32 {
+    let mut worst = 0.0f32;
+    for pair in original.windows(2) {
+        let (a, b) = (pair[0].time.max(start), pair[1].time.min(end));
+        if b <= a {
+            continue;
+        }
+        for k in 0..=SIMPLIFY_SAMPLES {
+            let t = a + (b - a) * k as f32 / SIMPLIFY_SAMPLES as f32;
+            worst = worst.max((evaluate(original, t, 0.0) - evaluate(candidate, t, 0.0)).abs());
+        }
+    }
+    worst
+}
+
+/// Removes keys whose absence keeps the curve within `tolerance` of the
+/// original, dropping the least significant key first.
+///
+/// The remaining keys are unchanged, and the deviation is always measured
+/// against the input curve so removals do not accumulate error.
+pub fn simplify_keyframes(keyframes: &[Keyframe], tolerance: f32) -> Vec<Keyframe> {
+    let mut result = keyframes.to_vec();
+    loop {
+        let mut best: Option<(usize, f32)> = None;
+        for i in 1..result.len().saturating_sub(1) {
+            let mut candidate = result.clone();
+            candidate.remove(i);
+            let deviation = max_deviation(keyframes, &candidate, result[i - 1].time, result[i + 1].time);
+            if deviation <= tolerance && best.is_none_or(|(_, d)| deviation < d) {
+                best = Some((i, deviation));
+            }
+        }
+        match best {
+            Some((i, _)) => {
+                result.remove(i);
+            }
+            None => return result,
+        }
     }
 }
 
@@ -365,6 +729,15 @@ mod tests {
         assert_relative_eq!(result, 200.0, epsilon = TOLERANCE);
     }
 
+    #[test]
+    fn evaluate_segment_zero_length_returns_end_value() {
+        let start = Keyframe::simple(1.0, 2.0);
+        let end = Keyframe::simple(1.0, 5.0);
+
+        assert_eq!(evaluate_segment(&start, &end, 1.0), 5.0);
+        assert_eq!(evaluate_segment(&linear_keyframe(1.0, 2.0), &linear_keyframe(1.0, 5.0), 1.0), 5.0);
+    }
+
     #[test]
     fn evaluate_linear_monotonic_behavior() {
         let keyframes = &[linear_keyframe(0.0, 0.0), linear_keyframe(1.0, 100.0)];
@@ -407,4 +780,309 @@ mod tests {
             t += 0.1;
         }
     }
+
+    fn mixed_keyframes() -> Vec<Keyframe> {
+        vec![
+            Keyframe::simple(0.0, 0.0),
+            linear_keyframe(0.5, 2.0),
+            constant_keyframe(1.0, -1.0),
+            Keyframe::new(2.0, 3.0, InterpolationType::Bezier, InterpolationType::Quintic, 1.5, -2.0, 0.4, 0.2),
+            Keyframe::simple(3.0, 1.0),
+        ]
+    }
+
+    #[test]
+    fn cursor_matches_evaluate_bit_for_bit() {
+        let keyframes = mixed_keyframes();
+        let mut cursor = KeyframeCursor::new(&keyframes);
+
+        for i in -10..=400 {
+            let t = i as f32 / 100.0;
+            assert_eq!(cursor.evaluate(t, 7.0).to_bits(), evaluate(&keyframes, t, 7.0).to_bits());
+        }
+    }
+
+    #[test]
+    fn cursor_handles_backward_steps() {
+        let keyframes = mixed_keyframes();
+        let mut cursor = KeyframeCursor::new(&keyframes);
+
+        for t in [2.5, 0.25, 2.9, 1.5, 0.0, 3.5, 0.75] {
+            assert_eq!(cursor.evaluate(t, 0.0).to_bits(), evaluate(&keyframes, t, 0.0).to_bits());
+        }
+    }
+
+    #[test]
+    fn evaluate_batch_matches_evaluate() {
+        let keyframes = mixed_keyframes();
+        let times = [0.1, 0.6, 1.2, 0.3, 2.7, 5.0];
+        let mut out = [0.0; 6];
+
+        evaluate_batch(&keyframes, &times, 0.0, &mut out);
+
+        for (&t, &value) in times.iter().zip(&out) {
+            assert_eq!(value.to_bits(), evaluate(&keyframes, t, 0.0).to_bits());
+        }
+        evaluate_batch(&[], &times[..2], 4.0, &mut out[..2]);
+        assert_eq!(&out[..2], &[4.0, 4.0]);
+    }
+
+    fn sample(f: impl Fn(f32) -> f32, count: usize, duration: f32) -> (Vec<f32>, Vec<f32>) {
+        let times: Vec<f32> = (0..count).map(|i| duration * i as f32 / (count - 1) as f32).collect();
+        let values = times.iter().map(|&t| f(t)).collect();
+        (times, values)
+    }
+
+    #[test]
+    fn fit_keyframes_reduces_line_to_endpoints() {
+        let (times, values) = sample(|t| 2.0 * t - 1.0, 101, 1.0);
+
+        let keys = fit_keyframes(&times, &values, 1e-4);
+
+        assert_eq!(keys.len(), 2);
+        assert_relative_eq!(keys[0].out_tangent, 2.0, epsilon = 1e-3);
+        assert_relative_eq!(keys[1].in_tangent, 2.0, epsilon = 1e-3);
+    }
+
+    #[test]
+    fn fit_keyframes_stays_within_tolerance() {
+        let (times, values) = sample(|t| (t * 2.0).sin() + 0.3 * (t * 5.0).cos(), 500, 5.0);
+
+        let keys = fit_keyframes(&times, &values, 1e-3);
+
+        assert!(keys.len() < 25, "{} keys", keys.len());
+        for (t, v) in times.iter().zip(&values) {
+            assert!((evaluate(&keys, *t, 0.0) - v).abs() <= 1e-3);
+        }
+    }
+
+    #[test]
+    fn fit_keyframes_handles_short_input() {
+        assert!(fit_keyframes(&[], &[], 1e-3).is_empty());
+        let keys = fit_keyframes(&[2.0], &[5.0], 1e-3);
+        assert_eq!(keys.len(), 1);
+        assert_relative_eq!(evaluate(&keys, 2.0, 0.0), 5.0);
+    }
+
+    #[test]
+    fn simplify_keyframes_removes_redundant_keys() {
+        let keys = [
+            linear_keyframe(0.0, 0.0),
+            linear_keyframe(1.0, 1.0),
+            linear_keyframe(2.0, 2.0),
+            linear_keyframe(3.0, 1.0),
+        ];
+
+        let simplified = simplify_keyframes(&keys, 1e-4);
+
+        assert_eq!(simplified, vec![keys[0], keys[2], keys[3]]);
+    }
+
+    #[test]
+    fn simplify_keyframes_respects_tolerance() {
+        let keys: Vec<Keyframe> = (0..=20)
+            .map(|i| {
+                let t = i as f32 * 0.25;
+                Keyframe::new(
+                    t,
+                    t.sin(),
+                    InterpolationType::Bezier,
+                    InterpolationType::Bezier,
+                    t.cos(),
+                    t.cos(),
+                    1.0 / 3.0,
+                    1.0 / 3.0,
+                )
+            })
+            .collect();
+
+        let simplified = simplify_keyframes(&keys, 1e-2);
+
+        assert!(simplified.len() < keys.len());
+        assert_eq!(simplified.first(), keys.first());
+        assert_eq!(simplified.last(), keys.last());
+        for i in 0..=500 {
+            let t = i as f32 * 0.01;
+            assert!((evaluate(&simplified, t, 0.0) - evaluate(&keys, t, 0.0)).abs() <= 1e-2);
+        }
+    }
+
+    fn keyed(handle_type: HandleType, points: &[(f32, f32)]) -> Vec<Keyframe> {
+        points
+            .iter()
+            .map(|&(t, v)| Keyframe::simple(t, v).with_handle_type(handle_type))
+            .collect()
+    }
+
+    #[test]
+    fn new_keyframe_is_free() {
+        assert_eq!(linear_keyframe(0.0, 1.0).handle_type, HandleType::Free);
+        assert_eq!(Keyframe::simple(0.0, 1.0).handle_type, HandleType::Free);
+    }
+
+    #[test]
+    fn recompute_tangents_leaves_free_keys() {
+        let mut keys = keyed(HandleType::Free, &[(0.0, 0.0), (1.0, 1.0), (2.0, 3.0)]);
+        keys[1].in_tangent = 5.0;
+        let before = keys.clone();
+
+        recompute_tangents(&mut keys);
+
+        assert_eq!(keys, before);
+    }
+
+    #[test]
+    fn recompute_tangents_auto_follows_neighbours_and_flattens_extrema() {
+        let mut keys = keyed(HandleType::Auto, &[(0.0, 0.0), (1.0, 1.0), (2.0, 3.0), (3.0, 1.0)]);
+        keys[1].in_weight = 0.5;
+
+        recompute_tangents(&mut keys);
+
+        assert_relative_eq!(keys[0].out_tangent, 0.0);
+        assert_relative_eq!(keys[1].in_tangent, 1.5);
+        assert_relative_eq!(keys[1].out_tangent, 1.5);
+        assert_relative_eq!(keys[1].in_weight, 1.0 / 3.0);
+        assert_relative_eq!(keys[2].out_tangent, 0.0);
+        assert_relative_eq!(keys[3].in_tangent, 0.0);
+    }
+
+    #[test]
+    fn recompute_tangents_vector_matches_linear() {
+        let mut keys = keyed(HandleType::Vector, &[(0.0, 0.0), (1.0, 2.0), (3.0, 0.0)]);
+
+        recompute_tangents(&mut keys);
+
+        let linear = [linear_keyframe(0.0, 0.0), linear_keyframe(1.0, 2.0), linear_keyframe(3.0, 0.0)];
+        for i in 0..=30 {
+            let t = i as f32 * 0.1;
+            assert_relative_eq!(evaluate(&keys, t, 0.0), evaluate(&linear, t, 0.0), epsilon = 1e-4);
+        }
+    }
+
+    #[test]
+    fn set_tangent_mirrors_aligned_handles() {
+        let mut keys = keyed(HandleType::Aligned, &[(0.0, 0.0), (1.0, 1.0)]);
+        keys[0].in_tangent = 1.0;
+        keys[0].out_weight = 0.5;
+
+        keys[0].set_tangent(HandleSide::Out, 3.0);
+        recompute_tangents(&mut keys);
+
+        assert_relative_eq!(keys[0].in_tangent, 3.0);
+        assert_relative_eq!(keys[0].out_tangent, 3.0);
+        assert_relative_eq!(keys[0].out_weight, 0.5);
+    }
+
+    #[test]
+    fn recompute_tangents_realigns_mismatched_aligned_keys() {
+        let mut keys = keyed(HandleType::Aligned, &[(0.0, 0.0), (1.0, 1.0), (2.0, 0.0)]);
+        keys[1].in_tangent = 1.0;
+        keys[1].out_tangent = 2.0;
+        keys[2].in_tangent = -1.0;
+        keys[2].out_tangent = 4.0;
+
+        recompute_tangents(&mut keys);
+        keys[2].time = 5.0;
+        recompute_tangents(&mut keys);
+
+        assert_relative_eq!(keys[1].in_tangent, 2.0);
+        assert_relative_eq!(keys[1].out_tangent, 2.0);
+        assert_relative_eq!(keys[2].in_tangent, -1.0);
+        assert_relative_eq!(keys[2].out_tangent, -1.0);
+    }
+
+    #[test]
+    fn set_tangent_moves_one_free_handle() {
+        let mut key = Keyframe::simple(0.0, 0.0);
+
+        key.set_tangent(HandleSide::In, 2.0);
+
+        assert_relative_eq!(key.in_tangent, 2.0);
+        assert_relative_eq!(key.out_tangent, 0.0);
+    }
+
+    fn eased_pair(interpolation: InterpolationType) -> [Keyframe; 2] {
+        let key = |time, value| {
+            Keyframe::new(time, value, interpolation, interpolation, 0.0, 0.0, 1.0 / 3.0, 1.0 / 3.0)
+        };
+        [key(1.0, 2.0), key(3.0, 6.0)]
+    }
+
+    #[test]
+    fn easing_types_hit_endpoints_and_rise_monotonically() {
+        for interpolation in [
+            InterpolationType::Cosine,
+            InterpolationType::Sinusoidal,
+            InterpolationType::Quintic,
+            InterpolationType::Plateau,
+        ] {
+            let keys = eased_pair(interpolation);
+
+            assert_relative_eq!(evaluate(&keys, 1.0, 0.0), 2.0, epsilon = TOLERANCE);
+            assert_relative_eq!(evaluate(&keys, 3.0 - 1e-4, 0.0), 6.0, epsilon = 1e-2);
+            let mut prev = 2.0;
+            for i in 1..100 {
+                let value = evaluate(&keys, 1.0 + i as f32 * 0.02, 0.0);
+                assert!(value >= prev - 1e-6, "{:?} not monotonic", interpolation);
+                prev = value;
+            }
+        }
+    }
+
+    #[test]
+    fn bezier_side_wins_over_easing() {
+        let key = |time, interpolation, tangent| {
+            Keyframe::new(time, time, interpolation, interpolation, tangent, tangent, 1.0 / 3.0, 1.0 / 3.0)
+        };
+        let bezier_first = [key(0.0, InterpolationType::Bezier, 3.0), key(1.0, InterpolationType::Cosine, 0.0)];
+        let cosine_first = [key(0.0, InterpolationType::Cosine, 0.0), key(1.0, InterpolationType::Bezier, 3.0)];
+
+        for [start, end] in [bezier_first, cosine_first] {
+            let value = evaluate_segment(&start, &end, 0.25);
+
+            assert_relative_eq!(value, evaluate_bezier_2d(&start, &end, 0.25), epsilon = TOLERANCE);
+        }
+        let cosine = 0.5 * (1.0 - (0.25 * PI).cos());
+        assert!(evaluate_segment(&bezier_first[0], &bezier_first[1], 0.25) > cosine + 0.1);
+    }
+
+    #[test]
+    fn symmetric_easing_types_pass_midpoint() {
+        for interpolation in [InterpolationType::Cosine, InterpolationType::Sinusoidal, InterpolationType::Quintic] {
+            assert_relative_eq!(evaluate(&eased_pair(interpolation), 2.0, 0.0), 4.0, epsilon = TOLERANCE);
+        }
+    }
+
+    #[test]
+    fn easing_types_start_flat() {
+        for interpolation in [
+            InterpolationType::Cosine,
+            InterpolationType::Sinusoidal,
+            InterpolationType::Quintic,
+            InterpolationType::Plateau,
+        ] {
+            let keys = eased_pair(interpolation);
+            let slope = (evaluate(&keys, 1.001, 0.0) - evaluate(&keys, 1.0, 0.0)) / 0.001;
+            assert!(slope.abs() < 0.02, "{:?} slope {}", interpolation, slope);
+        }
+    }
+
+    #[test]
+    fn plateau_reaches_end_value_early() {
+        let keys = eased_pair(InterpolationType::Plateau);
+
+        assert!(evaluate(&keys, 2.5, 0.0) > 5.95);
+    }
+
+    #[test]
+    fn quintic_is_c2_at_keys() {
+        let keys = eased_pair(InterpolationType::Quintic);
+        let h = 1e-2;
+        let curvature = |t: f32| {
+            (evaluate(&keys, t + h, 0.0) - 2.0 * evaluate(&keys, t, 0.0) + evaluate(&keys, t - h, 0.0)) / (h * h)
+        };
+
+        assert!(curvature(1.0 + h).abs() < 0.5);
+        assert!(curvature(3.0 - h).abs() < 0.5);
+    }
 }
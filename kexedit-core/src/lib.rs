@@ -1,3 +1,4 @@
+pub mod calculus;
 pub mod curvature;
 pub mod forces;
 pub mod frame;
@@ -7,13 +8,19 @@ pub mod math;
 pub mod physics_params;
 pub mod point;
 pub mod sim;
+pub mod validation;
 
+pub use calculus::{derivative, extrema, integrate, CurveExtrema};
 pub use curvature::Curvature;
 pub use forces::Forces;
 pub use frame::Frame;
 pub use frame_change::FrameChange;
-pub use keyframe::{evaluate, evaluate_segment, InterpolationType, Keyframe};
+pub use keyframe::{
+    evaluate, evaluate_batch, evaluate_segment, fit_keyframes, recompute_tangents, simplify_keyframes, HandleSide, HandleType,
+    InterpolationType, Keyframe, KeyframeCursor,
+};
 pub use math::{Float3, Quaternion};
 pub use physics_params::PhysicsParams;
 pub use point::Point;
 pub use sim::*;
+pub use validation::{normalize_keyframes, validate_keyframes, KeyframeIssue, KeyframeIssueKind};
@@ -19,6 +19,8 @@ pub struct Point {
     pub heart_offset: f32,
     pub friction: f32,
     pub resistance: f32,
+    /// Appended last so the offsets of the fields above match older builds.
+    pub longitudinal_force: f32,
 }
 
 impl Point {
@@ -32,6 +34,7 @@ impl Point {
         energy: f32,
         normal_force: f32,
         lateral_force: f32,
+        longitudinal_force: f32,
         heart_arc: f32,
         spine_arc: f32,
         heart_advance: f32,
@@ -50,6 +53,7 @@ impl Point {
             energy,
             normal_force,
             lateral_force,
+            longitudinal_force,
             heart_arc,
             spine_arc,
             heart_advance,
@@ -100,6 +104,7 @@ impl Point {
             0.0,
             0.0,
             0.0,
+            0.0,
             heart_offset,
             friction,
             resistance,
@@ -120,6 +125,7 @@ impl Point {
         0.0,
         0.0,
         0.0,
+        0.0,
         1.1,
         0.0,
         0.0,
@@ -135,6 +141,7 @@ impl Point {
             self.energy,
             self.normal_force,
             self.lateral_force,
+            self.longitudinal_force,
             self.heart_arc,
             self.spine_arc,
             self.heart_advance,
@@ -161,6 +168,7 @@ impl Point {
             new_energy,
             self.normal_force,
             self.lateral_force,
+            self.longitudinal_force,
             self.heart_arc,
             self.spine_arc,
             self.heart_advance,
@@ -182,6 +190,29 @@ impl Point {
             self.energy,
             new_normal_force,
             new_lateral_force,
+            self.longitudinal_force,
+            self.heart_arc,
+            self.spine_arc,
+            self.heart_advance,
+            self.friction_origin,
+            self.roll_speed,
+            self.heart_offset,
+            self.friction,
+            self.resistance,
+        )
+    }
+
+    pub fn with_longitudinal_force(&self, new_longitudinal_force: f32) -> Self {
+        Self::new(
+            self.heart_position,
+            self.direction,
+            self.normal,
+            self.lateral,
+            self.velocity,
+            self.energy,
+            self.normal_force,
+            self.lateral_force,
+            new_longitudinal_force,
             self.heart_arc,
             self.spine_arc,
             self.heart_advance,
@@ -218,6 +249,7 @@ impl Point {
             new_energy,
             self.normal_force,
             self.lateral_force,
+            self.longitudinal_force,
             self.heart_arc,
             self.spine_arc,
             self.heart_advance,
@@ -299,4 +331,14 @@ mod tests {
         assert_relative_eq!(updated.lateral_force, new_lateral, epsilon = TOLERANCE);
         assert_relative_eq!(updated.velocity, point.velocity, epsilon = TOLERANCE);
     }
+
+    #[test]
+    fn with_longitudinal_force_updates_only_longitudinal_force() {
+        let point = Point::DEFAULT;
+        let updated = point.with_longitudinal_force(-0.6);
+
+        assert_relative_eq!(updated.longitudinal_force, -0.6, epsilon = TOLERANCE);
+        assert_relative_eq!(updated.normal_force, point.normal_force, epsilon = TOLERANCE);
+        assert_relative_eq!(updated.velocity, point.velocity, epsilon = TOLERANCE);
+    }
 }
@@ -38,6 +38,10 @@ pub fn update_energy(
     (new_energy, new_velocity)
 }
 
+pub fn longitudinal_force(prev_velocity: f32, velocity: f32) -> f32 {
+    (velocity - prev_velocity) * HZ / G
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,6 +170,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn longitudinal_force_one_g_for_g_change_per_second() {
+        let force = longitudinal_force(10.0, 10.0 + G * DT);
+        assert_relative_eq!(force, 1.0, epsilon = TOLERANCE);
+        assert_relative_eq!(longitudinal_force(10.0, 10.0), 0.0, epsilon = TOLERANCE);
+        assert!(longitudinal_force(10.0, 9.9) < 0.0);
+    }
+
     #[test]
     fn update_energy_with_resistance_energy_decreases() {
         let prev_energy = 500.0;
@@ -0,0 +1,192 @@
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::keyframe::Keyframe;
+
+const DEFAULT_WEIGHT: f32 = 1.0 / 3.0;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum KeyframeIssueKind {
+    /// Time, value, a tangent or a weight is NaN or infinite.
+    NonFinite,
+    /// Earlier than the key before it.
+    Unsorted,
+    /// Same time as the key before it.
+    DuplicateTime,
+    /// A weight outside `[0, 1]`, which lets the Bezier fold back in time.
+    WeightOutOfRange,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct KeyframeIssue {
+    pub index: usize,
+    pub kind: KeyframeIssueKind,
+}
+
+impl fmt::Display for KeyframeIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self.kind {
+            KeyframeIssueKind::NonFinite => "has a non-finite field",
+            KeyframeIssueKind::Unsorted => "is earlier than the previous key",
+            KeyframeIssueKind::DuplicateTime => "has the same time as the previous key",
+            KeyframeIssueKind::WeightOutOfRange => "has a weight outside [0, 1]",
+        };
+        write!(f, "keyframe {} {}", self.index, description)
+    }
+}
+
+/// Every problem that would make `evaluate` return garbage, in key order.
+/// An empty result means the keys can be evaluated as they are.
+pub fn validate_keyframes(keyframes: &[Keyframe]) -> Vec<KeyframeIssue> {
+    let mut issues = Vec::new();
+    for (index, kf) in keyframes.iter().enumerate() {
+        let mut report = |kind| issues.push(KeyframeIssue { index, kind });
+
+        let fields = [kf.time, kf.value, kf.in_tangent, kf.out_tangent, kf.in_weight, kf.out_weight];
+        if fields.iter().any(|field| !field.is_finite()) {
+            report(KeyframeIssueKind::NonFinite);
+        }
+        if index > 0 {
+            let prev = keyframes[index - 1].time;
+            if kf.time < prev {
+                report(KeyframeIssueKind::Unsorted);
+            } else if kf.time == prev {
+                report(KeyframeIssueKind::DuplicateTime);
+            }
+        }
+        if [kf.in_weight, kf.out_weight].iter().any(|weight| !(0.0..=1.0).contains(weight)) {
+            report(KeyframeIssueKind::WeightOutOfRange);
+        }
+    }
+    issues
+}
+
+/// Repairs keys so that `validate_keyframes` finds nothing, borrowing them
+/// unchanged when they are already valid.
+///
+/// Keys with a non-finite time or value are dropped, non-finite tangents
+/// become flat and non-finite weights 1/3, and weights are clamped to
+/// `[0, 1]`. Keys are then stably sorted by time, and keys sharing a time are
+/// merged into one taking the in side of the first and the value and out side
+/// of the last, which is the value `evaluate` already returned at that time.
+pub fn normalize_keyframes(keyframes: &[Keyframe]) -> Cow<'_, [Keyframe]> {
+    if validate_keyframes(keyframes).is_empty() {
+        return Cow::Borrowed(keyframes);
+    }
+
+    let mut keys: Vec<Keyframe> = keyframes
+        .iter()
+        .filter(|kf| kf.time.is_finite() && kf.value.is_finite())
+        .map(|kf| Keyframe {
+            in_tangent: finite_or(kf.in_tangent, 0.0),
+            out_tangent: finite_or(kf.out_tangent, 0.0),
+            in_weight: finite_or(kf.in_weight, DEFAULT_WEIGHT).clamp(0.0, 1.0),
+            out_weight: finite_or(kf.out_weight, DEFAULT_WEIGHT).clamp(0.0, 1.0),
+            ..*kf
+        })
+        .collect();
+    keys.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+    let mut merged: Vec<Keyframe> = Vec::with_capacity(keys.len());
+    for kf in keys {
+        match merged.last_mut() {
+            Some(last) if last.time == kf.time => {
+                *last = Keyframe {
+                    in_interpolation: last.in_interpolation,
+                    in_tangent: last.in_tangent,
+                    in_weight: last.in_weight,
+                    ..kf
+                };
+            }
+            _ => merged.push(kf),
+        }
+    }
+    Cow::Owned(merged)
+}
+
+fn finite_or(value: f32, fallback: f32) -> f32 {
+    if value.is_finite() {
+        value
+    } else {
+        fallback
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyframe::{evaluate, InterpolationType};
+
+    fn issue(index: usize, kind: KeyframeIssueKind) -> KeyframeIssue {
+        KeyframeIssue { index, kind }
+    }
+
+    #[test]
+    fn valid_keys_have_no_issues_and_are_borrowed() {
+        let keys = [Keyframe::simple(0.0, 1.0), Keyframe::simple(1.0, 2.0)];
+
+        assert!(validate_keyframes(&keys).is_empty());
+        assert!(matches!(normalize_keyframes(&keys), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn validate_reports_each_problem() {
+        let mut bad_weight = Keyframe::simple(3.0, 0.0);
+        bad_weight.out_weight = 1.5;
+        let keys = [
+            Keyframe::simple(0.0, 0.0),
+            Keyframe::simple(2.0, f32::NAN),
+            Keyframe::simple(1.0, 0.0),
+            Keyframe::simple(1.0, 0.0),
+            bad_weight,
+        ];
+
+        assert_eq!(
+            validate_keyframes(&keys),
+            vec![
+                issue(1, KeyframeIssueKind::NonFinite),
+                issue(2, KeyframeIssueKind::Unsorted),
+                issue(3, KeyframeIssueKind::DuplicateTime),
+                issue(4, KeyframeIssueKind::WeightOutOfRange),
+            ]
+        );
+        assert_eq!(issue(3, KeyframeIssueKind::DuplicateTime).to_string(), "keyframe 3 has the same time as the previous key");
+    }
+
+    #[test]
+    fn normalize_sorts_merges_and_clamps() {
+        let mut first = Keyframe::new(1.0, 5.0, InterpolationType::Linear, InterpolationType::Constant, 0.0, 0.0, 2.0, -1.0);
+        first.in_tangent = f32::INFINITY;
+        let keys = [
+            Keyframe::simple(2.0, 3.0),
+            first,
+            Keyframe::simple(f32::NAN, 9.0),
+            Keyframe::simple(0.0, 1.0),
+            Keyframe::simple(1.0, 6.0),
+        ];
+
+        let normalized = normalize_keyframes(&keys);
+
+        assert!(validate_keyframes(&normalized).is_empty());
+        let times: Vec<f32> = normalized.iter().map(|kf| kf.time).collect();
+        assert_eq!(times, vec![0.0, 1.0, 2.0]);
+        let merged = normalized[1];
+        assert_eq!(merged.value, 6.0);
+        assert_eq!(merged.in_interpolation, InterpolationType::Linear);
+        assert_eq!(merged.in_tangent, 0.0);
+        assert_eq!(merged.in_weight, 1.0);
+        assert_eq!(merged.out_interpolation, InterpolationType::Bezier);
+    }
+
+    #[test]
+    fn normalize_keeps_evaluate_result_at_duplicate_time() {
+        let keys = [Keyframe::simple(0.0, 0.0), Keyframe::simple(1.0, 2.0), Keyframe::simple(1.0, 4.0), Keyframe::simple(2.0, 0.0)];
+
+        let normalized = normalize_keyframes(&keys);
+
+        assert_eq!(evaluate(&normalized, 1.0, 0.0), evaluate(&keys, 1.0, 0.0));
+        assert_eq!(evaluate(&normalized, 1.5, 0.0), evaluate(&keys, 1.5, 0.0));
+    }
+}
@@ -1,4 +1,8 @@
-use kexedit_core::{Frame, Float3, Point, Keyframe, Quaternion};
+use std::borrow::Cow;
+
+use kexedit_core::{CurveExtrema, Frame, Float3, HandleSide, Point, Keyframe, KeyframeIssue, Quaternion};
+use kexedit_nodes::analysis::stats::RideStats;
+use kexedit_nodes::roll::RollChannel;
 use kexedit_nodes::{DurationType, IterationConfig};
 
 #[no_mangle]
@@ -77,6 +81,15 @@ pub unsafe extern "C" fn kexedit_frame_roll(frame: *const Frame) -> f32 {
     frame_val.roll()
 }
 
+/// Reads a caller's keyframe array, normalised if it fails validation so that
+/// unsorted, duplicate or non-finite keys never reach the evaluator.
+unsafe fn keyframe_input<'a>(keyframes: *const Keyframe, keyframes_len: usize) -> Cow<'a, [Keyframe]> {
+    if keyframes.is_null() || keyframes_len == 0 {
+        return Cow::Borrowed(&[]);
+    }
+    kexedit_core::normalize_keyframes(std::slice::from_raw_parts(keyframes, keyframes_len))
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn kexedit_force_build(
     anchor: *const Point,
@@ -103,6 +116,65 @@ pub unsafe extern "C" fn kexedit_force_build(
     out_points: *mut Point,
     out_len: *mut usize,
     max_len: usize,
+) -> i32 {
+    kexedit_force_build_ex(
+        anchor,
+        duration,
+        duration_type,
+        driven,
+        RollChannel::Speed as u8,
+        roll_speed,
+        roll_speed_len,
+        normal_force,
+        normal_force_len,
+        lateral_force,
+        lateral_force_len,
+        driven_velocity,
+        driven_velocity_len,
+        heart_offset,
+        heart_offset_len,
+        friction,
+        friction_len,
+        resistance,
+        resistance_len,
+        anchor_heart,
+        anchor_friction,
+        anchor_resistance,
+        out_points,
+        out_len,
+        max_len,
+    )
+}
+
+/// `kexedit_force_build` with the roll keys read as roll speed (`roll_channel`
+/// 0) or absolute bank angle (1).
+#[no_mangle]
+pub unsafe extern "C" fn kexedit_force_build_ex(
+    anchor: *const Point,
+    duration: f32,
+    duration_type: i32,
+    driven: bool,
+    roll_channel: u8,
+    roll: *const Keyframe,
+    roll_len: usize,
+    normal_force: *const Keyframe,
+    normal_force_len: usize,
+    lateral_force: *const Keyframe,
+    lateral_force_len: usize,
+    driven_velocity: *const Keyframe,
+    driven_velocity_len: usize,
+    heart_offset: *const Keyframe,
+    heart_offset_len: usize,
+    friction: *const Keyframe,
+    friction_len: usize,
+    resistance: *const Keyframe,
+    resistance_len: usize,
+    anchor_heart: f32,
+    anchor_friction: f32,
+    anchor_resistance: f32,
+    out_points: *mut Point,
+    out_len: *mut usize,
+    max_len: usize,
 ) -> i32 {
     if anchor.is_null() || out_points.is_null() || out_len.is_null() {
         return -1;
@@ -117,54 +189,32 @@ pub unsafe extern "C" fn kexedit_force_build(
             _ => return -2,
         },
     );
-
-    let roll_speed_slice = if roll_speed.is_null() {
-        &[]
-    } else {
-        std::slice::from_raw_parts(roll_speed, roll_speed_len)
-    };
-    let normal_force_slice = if normal_force.is_null() {
-        &[]
-    } else {
-        std::slice::from_raw_parts(normal_force, normal_force_len)
-    };
-    let lateral_force_slice = if lateral_force.is_null() {
-        &[]
-    } else {
-        std::slice::from_raw_parts(lateral_force, lateral_force_len)
-    };
-    let driven_velocity_slice = if driven_velocity.is_null() {
-        &[]
-    } else {
-        std::slice::from_raw_parts(driven_velocity, driven_velocity_len)
-    };
-    let heart_offset_slice = if heart_offset.is_null() {
-        &[]
-    } else {
-        std::slice::from_raw_parts(heart_offset, heart_offset_len)
-    };
-    let friction_slice = if friction.is_null() {
-        &[]
-    } else {
-        std::slice::from_raw_parts(friction, friction_len)
-    };
-    let resistance_slice = if resistance.is_null() {
-        &[]
-    } else {
-        std::slice::from_raw_parts(resistance, resistance_len)
+    let roll_channel = match roll_channel {
+        0 => RollChannel::Speed,
+        1 => RollChannel::Angle,
+        _ => return -2,
     };
 
+    let roll_slice = keyframe_input(roll, roll_len);
+    let normal_force_slice = keyframe_input(normal_force, normal_force_len);
+    let lateral_force_slice = keyframe_input(lateral_force, lateral_force_len);
+    let driven_velocity_slice = keyframe_input(driven_velocity, driven_velocity_len);
+    let heart_offset_slice = keyframe_input(heart_offset, heart_offset_len);
+    let friction_slice = keyframe_input(friction, friction_len);
+    let resistance_slice = keyframe_input(resistance, resistance_len);
+
     let result = kexedit_nodes::force::build(
         &anchor_val,
         &config,
         driven,
-        roll_speed_slice,
-        normal_force_slice,
-        lateral_force_slice,
-        driven_velocity_slice,
-        heart_offset_slice,
-        friction_slice,
-        resistance_slice,
+        roll_channel,
+        &roll_slice,
+        &normal_force_slice,
+        &lateral_force_slice,
+        &driven_velocity_slice,
+        &heart_offset_slice,
+        &friction_slice,
+        &resistance_slice,
         anchor_heart,
         anchor_friction,
         anchor_resistance,
@@ -183,6 +233,52 @@ pub unsafe extern "C" fn kexedit_force_build(
     0
 }
 
+/// Computes ride statistics over `points`, which holds `path_count` consecutive
+/// paths whose lengths are given by `path_lens`. A null `path_lens` treats
+/// `points` as a single path. Returns -3 unless the lengths add up to
+/// exactly `points_len`.
+#[no_mangle]
+pub unsafe extern "C" fn kexedit_ride_stats(
+    points: *const Point,
+    points_len: usize,
+    path_lens: *const usize,
+    path_count: usize,
+    out: *mut RideStats,
+) -> i32 {
+    if out.is_null() || (points.is_null() && points_len > 0) {
+        return -1;
+    }
+
+    let points_slice = if points.is_null() {
+        &[]
+    } else {
+        std::slice::from_raw_parts(points, points_len)
+    };
+
+    if path_lens.is_null() {
+        *out = RideStats::from_path(points_slice);
+        return 0;
+    }
+
+    let lens = std::slice::from_raw_parts(path_lens, path_count);
+    let mut paths = Vec::with_capacity(path_count);
+    let mut start = 0usize;
+    for &len in lens {
+        let end = match start.checked_add(len) {
+            Some(end) if end <= points_slice.len() => end,
+            _ => return -3,
+        };
+        paths.push(&points_slice[start..end]);
+        start = end;
+    }
+    if start != points_slice.len() {
+        return -3;
+    }
+
+    *out = RideStats::from_paths(&paths);
+    0
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn kexedit_keyframe_evaluate(
     keyframes: *const Keyframe,
@@ -190,12 +286,152 @@ pub unsafe extern "C" fn kexedit_keyframe_evaluate(
     t: f32,
     default_value: f32,
 ) -> f32 {
-    let keyframes_slice = if keyframes.is_null() || keyframes_len == 0 {
-        &[]
+    let keyframes_slice = keyframe_input(keyframes, keyframes_len);
+    kexedit_core::evaluate(&keyframes_slice, t, default_value)
+}
+
+/// Evaluates the curve at each of `times_len` times into `out`.
+#[no_mangle]
+pub unsafe extern "C" fn kexedit_keyframe_evaluate_batch(
+    keyframes: *const Keyframe,
+    keyframes_len: usize,
+    times: *const f32,
+    times_len: usize,
+    default_value: f32,
+    out: *mut f32,
+) -> i32 {
+    if (keyframes.is_null() && keyframes_len > 0) || ((times.is_null() || out.is_null()) && times_len > 0) {
+        return -1;
+    }
+    if times_len == 0 {
+        return 0;
+    }
+    let keyframes_slice = keyframe_input(keyframes, keyframes_len);
+    kexedit_core::evaluate_batch(
+        &keyframes_slice,
+        std::slice::from_raw_parts(times, times_len),
+        default_value,
+        std::slice::from_raw_parts_mut(out, times_len),
+    );
+    0
+}
+
+/// Definite integral of the curve over `[from, to]`.
+#[no_mangle]
+pub unsafe extern "C" fn kexedit_keyframe_integrate(
+    keyframes: *const Keyframe,
+    keyframes_len: usize,
+    from: f32,
+    to: f32,
+    default_value: f32,
+) -> f32 {
+    let keyframes_slice = keyframe_input(keyframes, keyframes_len);
+    kexedit_core::integrate(&keyframes_slice, from, to, default_value)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn kexedit_keyframe_derivative(keyframes: *const Keyframe, keyframes_len: usize, t: f32) -> f32 {
+    kexedit_core::derivative(&keyframe_input(keyframes, keyframes_len), t)
+}
+
+/// Writes the curve's min and max over `[from, to]` to `out`.
+#[no_mangle]
+pub unsafe extern "C" fn kexedit_keyframe_extrema(
+    keyframes: *const Keyframe,
+    keyframes_len: usize,
+    from: f32,
+    to: f32,
+    default_value: f32,
+    out: *mut CurveExtrema,
+) -> i32 {
+    if out.is_null() || (keyframes.is_null() && keyframes_len > 0) {
+        return -1;
+    }
+    let keyframes_slice = keyframe_input(keyframes, keyframes_len);
+    *out = kexedit_core::extrema(&keyframes_slice, from, to, default_value);
+    0
+}
+
+/// Writes up to `max_issues` validation issues to `out_issues` and the total
+/// count to `out_count`. Returns -3 if the issues did not all fit.
+#[no_mangle]
+pub unsafe extern "C" fn kexedit_keyframes_validate(
+    keyframes: *const Keyframe,
+    keyframes_len: usize,
+    out_issues: *mut KeyframeIssue,
+    max_issues: usize,
+    out_count: *mut usize,
+) -> i32 {
+    if out_count.is_null() || (keyframes.is_null() && keyframes_len > 0) || (out_issues.is_null() && max_issues > 0) {
+        return -1;
+    }
+    let issues = if keyframes_len == 0 {
+        Vec::new()
     } else {
-        std::slice::from_raw_parts(keyframes, keyframes_len)
+        kexedit_core::validate_keyframes(std::slice::from_raw_parts(keyframes, keyframes_len))
     };
-    kexedit_core::evaluate(keyframes_slice, t, default_value)
+
+    for (i, issue) in issues.iter().take(max_issues).enumerate() {
+        *out_issues.add(i) = *issue;
+    }
+    *out_count = issues.len();
+    if issues.len() > max_issues {
+        return -3;
+    }
+    0
+}
+
+/// Normalises keys in place; merging and dropping keys can only shorten the
+/// array, so the new length is written to `out_len`.
+#[no_mangle]
+pub unsafe extern "C" fn kexedit_keyframes_normalize(
+    keyframes: *mut Keyframe,
+    keyframes_len: usize,
+    out_len: *mut usize,
+) -> i32 {
+    if out_len.is_null() || (keyframes.is_null() && keyframes_len > 0) {
+        return -1;
+    }
+    if keyframes_len == 0 {
+        *out_len = 0;
+        return 0;
+    }
+    let normalized = kexedit_core::normalize_keyframes(std::slice::from_raw_parts(keyframes, keyframes_len)).into_owned();
+    for (i, keyframe) in normalized.iter().enumerate() {
+        *keyframes.add(i) = *keyframe;
+    }
+    *out_len = normalized.len();
+    0
+}
+
+/// Recomputes tangents of `Auto` and `Vector` keys and re-aligns `Aligned` keys
+/// in place.
+#[no_mangle]
+pub unsafe extern "C" fn kexedit_keyframes_recompute_tangents(keyframes: *mut Keyframe, keyframes_len: usize) -> i32 {
+    if keyframes.is_null() && keyframes_len > 0 {
+        return -1;
+    }
+    if keyframes_len == 0 {
+        return 0;
+    }
+    kexedit_core::recompute_tangents(std::slice::from_raw_parts_mut(keyframes, keyframes_len));
+    0
+}
+
+/// Sets the in (`side` 0) or out (`side` 1) tangent of `keyframe`, mirroring
+/// it to the other side on `Aligned` keys.
+#[no_mangle]
+pub unsafe extern "C" fn kexedit_keyframe_set_tangent(keyframe: *mut Keyframe, side: u8, tangent: f32) -> i32 {
+    if keyframe.is_null() {
+        return -1;
+    }
+    let side = match side {
+        0 => HandleSide::In,
+        1 => HandleSide::Out,
+        _ => return -2,
+    };
+    (*keyframe).set_tangent(side, tangent);
+    0
 }
 
 #[no_mangle]
@@ -242,4 +478,237 @@ mod tests {
             kexedit_frame_rotate_around(&frame, &axis, angle, std::ptr::null_mut());
         }
     }
+
+    #[test]
+    fn test_ffi_force_build_exports_longitudinal_force() {
+        let anchor = Point::create(Float3::new(0.0, 20.0, 0.0), Float3::BACK, 0.0, 10.0, 1.1, 0.0, 0.0);
+        let normal_force = [Keyframe::simple(0.0, 0.0)];
+        let mut out = vec![Point::DEFAULT; 128];
+        let mut out_len = 0;
+
+        let status = unsafe {
+            kexedit_force_build(
+                &anchor,
+                1.0,
+                0,
+                false,
+                std::ptr::null(),
+                0,
+                normal_force.as_ptr(),
+                normal_force.len(),
+                std::ptr::null(),
+                0,
+                std::ptr::null(),
+                0,
+                std::ptr::null(),
+                0,
+                std::ptr::null(),
+                0,
+                std::ptr::null(),
+                0,
+                1.1,
+                0.0,
+                0.0,
+                out.as_mut_ptr(),
+                &mut out_len,
+                out.len(),
+            )
+        };
+
+        assert_eq!(status, 0);
+        assert!(out_len > 10);
+        assert!(out[out_len - 1].longitudinal_force > 0.5);
+    }
+
+    #[test]
+    fn test_ffi_force_build_selects_roll_channel() {
+        let anchor = Point::create(Float3::new(0.0, 20.0, 0.0), Float3::BACK, 0.0, 10.0, 1.1, 0.0, 0.0);
+        let roll = [Keyframe::simple(0.0, 0.5)];
+        let mut out = vec![Point::DEFAULT; 128];
+        let build = |roll_channel: u8, out: &mut [Point], out_len: &mut usize| unsafe {
+            kexedit_force_build_ex(
+                &anchor,
+                1.0,
+                0,
+                false,
+                roll_channel,
+                roll.as_ptr(),
+                roll.len(),
+                std::ptr::null(),
+                0,
+                std::ptr::null(),
+                0,
+                std::ptr::null(),
+                0,
+                std::ptr::null(),
+                0,
+                std::ptr::null(),
+                0,
+                std::ptr::null(),
+                0,
+                1.1,
+                0.0,
+                0.0,
+                out.as_mut_ptr(),
+                out_len,
+                out.len(),
+            )
+        };
+
+        let mut out_len = 0;
+        assert_eq!(build(1, &mut out, &mut out_len), 0);
+        assert!((out[out_len - 1].roll() - 0.5).abs() < 1e-3);
+        assert_eq!(build(2, &mut out, &mut out_len), -2);
+    }
+
+    #[test]
+    fn test_ffi_ride_stats_splits_paths() {
+        let mut points = [Point::DEFAULT; 5];
+        for (i, point) in points.iter_mut().enumerate() {
+            point.heart_arc = i as f32;
+        }
+        points[3].heart_position = Float3::new(0.0, 50.0, 0.0);
+        let path_lens = [2usize, 3];
+        let mut out = RideStats::default();
+
+        let status = unsafe {
+            kexedit_ride_stats(points.as_ptr(), points.len(), path_lens.as_ptr(), path_lens.len(), &mut out)
+        };
+
+        assert_eq!(status, 0);
+        assert!((out.max_height.value - 50.0).abs() < 1e-6);
+        assert!((out.length - 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_ffi_ride_stats_rejects_bad_lengths() {
+        let points = [Point::DEFAULT; 2];
+        let path_lens = [3usize];
+        let mut out = RideStats::default();
+
+        unsafe {
+            assert_eq!(kexedit_ride_stats(points.as_ptr(), 2, std::ptr::null(), 0, std::ptr::null_mut()), -1);
+            assert_eq!(kexedit_ride_stats(points.as_ptr(), 2, path_lens.as_ptr(), 1, &mut out), -3);
+            let overflowing = [1usize, usize::MAX];
+            assert_eq!(kexedit_ride_stats(points.as_ptr(), 2, overflowing.as_ptr(), 2, &mut out), -3);
+            let short = [1usize];
+            assert_eq!(kexedit_ride_stats(points.as_ptr(), 2, short.as_ptr(), 1, &mut out), -3);
+        }
+    }
+
+    #[test]
+    fn test_ffi_recompute_tangents_updates_auto_keys() {
+        let mut keyframes = [
+            Keyframe::simple(0.0, 0.0),
+            Keyframe::simple(1.0, 1.0).with_handle_type(kexedit_core::HandleType::Auto),
+            Keyframe::simple(2.0, 3.0),
+        ];
+
+        let status = unsafe { kexedit_keyframes_recompute_tangents(keyframes.as_mut_ptr(), keyframes.len()) };
+
+        assert_eq!(status, 0);
+        assert!((keyframes[1].out_tangent - 1.5).abs() < 1e-6);
+        assert_eq!(unsafe { kexedit_keyframes_recompute_tangents(std::ptr::null_mut(), 3) }, -1);
+    }
+
+    #[test]
+    fn test_ffi_set_tangent_mirrors_aligned_keys() {
+        let mut keyframe = Keyframe::simple(0.0, 0.0).with_handle_type(kexedit_core::HandleType::Aligned);
+
+        assert_eq!(unsafe { kexedit_keyframe_set_tangent(&mut keyframe, 0, 2.0) }, 0);
+        assert_eq!(keyframe.out_tangent, 2.0);
+        assert_eq!(unsafe { kexedit_keyframe_set_tangent(&mut keyframe, 2, 1.0) }, -2);
+        assert_eq!(unsafe { kexedit_keyframe_set_tangent(std::ptr::null_mut(), 0, 1.0) }, -1);
+    }
+
+    #[test]
+    fn test_ffi_point_layout() {
+        // The managed Point struct mirrors this layout field for field.
+        assert_eq!(std::mem::size_of::<Point>(), 100);
+        assert_eq!(std::mem::align_of::<Point>(), 4);
+        assert_eq!(std::mem::offset_of!(Point, velocity), 48);
+        assert_eq!(std::mem::offset_of!(Point, heart_arc), 64);
+        assert_eq!(std::mem::offset_of!(Point, resistance), 92);
+        assert_eq!(std::mem::offset_of!(Point, longitudinal_force), 96);
+    }
+
+    #[test]
+    fn test_ffi_keyframe_layout() {
+        // The managed Keyframe struct mirrors this layout field for field.
+        assert_eq!(std::mem::size_of::<Keyframe>(), 36);
+        assert_eq!(std::mem::align_of::<Keyframe>(), 4);
+        assert_eq!(std::mem::offset_of!(Keyframe, handle_type), 32);
+    }
+
+    #[test]
+    fn test_ffi_keyframe_calculus() {
+        use kexedit_core::InterpolationType;
+        let keyframes = [
+            Keyframe::new(0.0, 0.0, InterpolationType::Linear, InterpolationType::Linear, 0.0, 0.0, 1.0 / 3.0, 1.0 / 3.0),
+            Keyframe::new(2.0, 2.0, InterpolationType::Linear, InterpolationType::Linear, 0.0, 0.0, 1.0 / 3.0, 1.0 / 3.0),
+        ];
+        let mut out = CurveExtrema::default();
+
+        let total = unsafe { kexedit_keyframe_integrate(keyframes.as_ptr(), keyframes.len(), 0.0, 2.0, 0.0) };
+        let slope = unsafe { kexedit_keyframe_derivative(keyframes.as_ptr(), keyframes.len(), 1.0) };
+        let status = unsafe { kexedit_keyframe_extrema(keyframes.as_ptr(), keyframes.len(), 0.5, 3.0, 0.0, &mut out) };
+
+        assert!((total - 2.0).abs() < 1e-6);
+        assert!((slope - 1.0).abs() < 1e-6);
+        assert_eq!(status, 0);
+        assert!((out.min - 0.5).abs() < 1e-6);
+        assert!((out.max - 2.0).abs() < 1e-6);
+        assert_eq!(unsafe { kexedit_keyframe_extrema(keyframes.as_ptr(), 2, 0.0, 1.0, 0.0, std::ptr::null_mut()) }, -1);
+    }
+
+    #[test]
+    fn test_ffi_keyframe_evaluate_batch() {
+        let keyframes = [Keyframe::simple(0.0, 0.0), Keyframe::simple(1.0, 2.0)];
+        let times = [0.0, 0.25, 0.5, 2.0];
+        let mut out = [0.0; 4];
+
+        let status = unsafe {
+            kexedit_keyframe_evaluate_batch(keyframes.as_ptr(), keyframes.len(), times.as_ptr(), times.len(), 0.0, out.as_mut_ptr())
+        };
+
+        assert_eq!(status, 0);
+        for (&t, &value) in times.iter().zip(&out) {
+            assert_eq!(value, kexedit_core::evaluate(&keyframes, t, 0.0));
+        }
+        assert_eq!(
+            unsafe { kexedit_keyframe_evaluate_batch(keyframes.as_ptr(), 2, std::ptr::null(), 4, 0.0, out.as_mut_ptr()) },
+            -1
+        );
+    }
+
+    #[test]
+    fn test_ffi_keyframes_validate_and_normalize() {
+        let mut keyframes = [Keyframe::simple(1.0, 1.0), Keyframe::simple(0.0, 0.0), Keyframe::simple(0.0, 2.0)];
+        let mut issues = [KeyframeIssue { index: 0, kind: kexedit_core::KeyframeIssueKind::NonFinite }; 1];
+        let mut count = 0;
+        let mut len = 0;
+
+        let status = unsafe {
+            kexedit_keyframes_validate(keyframes.as_ptr(), keyframes.len(), issues.as_mut_ptr(), issues.len(), &mut count)
+        };
+        assert_eq!(status, -3);
+        assert_eq!(count, 2);
+        assert_eq!(issues[0].kind, kexedit_core::KeyframeIssueKind::Unsorted);
+
+        let status = unsafe { kexedit_keyframes_normalize(keyframes.as_mut_ptr(), keyframes.len(), &mut len) };
+        assert_eq!(status, 0);
+        assert_eq!(len, 2);
+        assert_eq!(keyframes[0].value, 2.0);
+        assert_eq!(keyframes[1].time, 1.0);
+    }
+
+    #[test]
+    fn test_ffi_evaluate_normalizes_unsorted_input() {
+        let unsorted = [Keyframe::simple(1.0, 2.0), Keyframe::simple(0.0, 0.0)];
+        let sorted = [Keyframe::simple(0.0, 0.0), Keyframe::simple(1.0, 2.0)];
+
+        let value = unsafe { kexedit_keyframe_evaluate(unsorted.as_ptr(), unsorted.len(), 0.5, 0.0) };
+
+        assert_eq!(value, kexedit_core::evaluate(&sorted, 0.5, 0.0));
+    }
 }
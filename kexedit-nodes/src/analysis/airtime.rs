@@ -0,0 +1,166 @@
+use kexedit_core::{sim, Point};
+
+use super::{join_paths, spans};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AirtimeThresholds {
+    /// Normal force below which riders float, in G.
+    pub floater: f32,
+    /// Normal force below which riders are pulled from the seat, in G.
+    pub ejector: f32,
+}
+
+impl AirtimeThresholds {
+    pub const DEFAULT: Self = Self::new(0.2, 0.0);
+
+    pub const fn new(floater: f32, ejector: f32) -> Self {
+        Self { floater, ejector }
+    }
+}
+
+impl Default for AirtimeThresholds {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AirtimeKind {
+    Floater,
+    /// The span dips below the ejector threshold at least once.
+    Ejector,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AirtimeSpan {
+    pub kind: AirtimeKind,
+    pub start_index: usize,
+    /// Exclusive.
+    pub end_index: usize,
+    pub start_arc: f32,
+    pub end_arc: f32,
+    /// Seconds.
+    pub duration: f32,
+    pub min_normal_force: f32,
+    pub average_lateral_force: f32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AirtimeReport {
+    pub spans: Vec<AirtimeSpan>,
+    /// Seconds below the floater threshold, including ejector time.
+    pub total: f32,
+    /// Seconds below the ejector threshold.
+    pub ejector: f32,
+}
+
+pub fn analyze(points: &[Point], thresholds: &AirtimeThresholds) -> AirtimeReport {
+    let mut report = AirtimeReport::default();
+
+    for span in spans(points.len(), |i| points[i].normal_force < thresholds.floater) {
+        let window = &points[span.clone()];
+        let min_normal_force = window
+            .iter()
+            .fold(f32::INFINITY, |acc, p| acc.min(p.normal_force));
+        let average_lateral_force =
+            window.iter().map(|p| p.lateral_force).sum::<f32>() / window.len() as f32;
+        let duration = window.len() as f32 * sim::DT;
+        let ejector_samples = window
+            .iter()
+            .filter(|p| p.normal_force < thresholds.ejector)
+            .count();
+
+        report.total += duration;
+        report.ejector += ejector_samples as f32 * sim::DT;
+        report.spans.push(AirtimeSpan {
+            kind: if ejector_samples > 0 {
+                AirtimeKind::Ejector
+            } else {
+                AirtimeKind::Floater
+            },
+            start_index: span.start,
+            end_index: span.end,
+            start_arc: window[0].heart_arc,
+            end_arc: window[window.len() - 1].heart_arc,
+            duration,
+            min_normal_force,
+            average_lateral_force,
+        });
+    }
+
+    report
+}
+
+/// Analyzes consecutive paths as one ride, so spans continue across node
+/// boundaries. Indices and arcs refer to the joined ride, where each later
+/// path's anchor (a copy of the previous path's last point) is dropped.
+pub fn analyze_paths(paths: &[&[Point]], thresholds: &AirtimeThresholds) -> AirtimeReport {
+    analyze(&join_paths(paths), thresholds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn path(normal_forces: &[f32]) -> Vec<Point> {
+        normal_forces
+            .iter()
+            .enumerate()
+            .map(|(i, &n)| {
+                let mut point = Point::DEFAULT.with_forces(n, 0.1 * i as f32);
+                point.heart_arc = i as f32;
+                point
+            })
+            .collect()
+    }
+
+    #[test]
+    fn finds_floater_and_ejector_spans() {
+        let points = path(&[1.0, 0.1, 0.0, 1.0, 1.0, -0.5, -0.2, 0.1, 1.0]);
+
+        let report = analyze(&points, &AirtimeThresholds::DEFAULT);
+
+        assert_eq!(report.spans.len(), 2);
+        let floater = report.spans[0];
+        assert_eq!(floater.kind, AirtimeKind::Floater);
+        assert_eq!((floater.start_index, floater.end_index), (1, 3));
+        assert_relative_eq!(floater.start_arc, 1.0);
+        assert_relative_eq!(floater.end_arc, 2.0);
+        assert_relative_eq!(floater.duration, 2.0 * sim::DT);
+        assert_relative_eq!(floater.min_normal_force, 0.0);
+        assert_relative_eq!(floater.average_lateral_force, 0.15, epsilon = 1e-6);
+
+        let ejector = report.spans[1];
+        assert_eq!(ejector.kind, AirtimeKind::Ejector);
+        assert_eq!((ejector.start_index, ejector.end_index), (5, 8));
+        assert_relative_eq!(ejector.min_normal_force, -0.5);
+
+        assert_relative_eq!(report.total, 5.0 * sim::DT);
+        assert_relative_eq!(report.ejector, 2.0 * sim::DT);
+    }
+
+    #[test]
+    fn thresholds_are_configurable() {
+        let points = path(&[1.0, 0.4, 0.4, 1.0]);
+
+        assert!(analyze(&points, &AirtimeThresholds::DEFAULT).spans.is_empty());
+        assert_eq!(analyze(&points, &AirtimeThresholds::new(0.5, 0.3)).spans.len(), 1);
+    }
+
+    #[test]
+    fn analyze_paths_joins_span_across_boundary() {
+        let first = path(&[1.0, 0.1, 0.1]);
+        let mut second = path(&[0.1, 0.1, 0.1, 1.0]);
+        for (i, p) in second.iter_mut().enumerate() {
+            p.heart_arc = 2.0 + i as f32;
+        }
+
+        let report = analyze_paths(&[&first, &second], &AirtimeThresholds::DEFAULT);
+
+        assert_eq!(report.spans.len(), 1);
+        assert_eq!((report.spans[0].start_index, report.spans[0].end_index), (1, 5));
+        assert_relative_eq!(report.spans[0].end_arc, 4.0);
+        assert_relative_eq!(report.total, 4.0 * sim::DT);
+    }
+}
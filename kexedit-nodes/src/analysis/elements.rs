@@ -0,0 +1,345 @@
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+use std::ops::Range;
+
+use kexedit_core::{sim, Point};
+
+use super::{local_rotation, spans};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ElementConfig {
+    /// Angle between the rider's up and world up beyond which a point counts as inverted.
+    pub inversion_threshold: f32,
+    /// Largest heart-line gap between two inversions that can form a cobra roll.
+    pub cobra_gap: f32,
+    /// Smallest heading change across a cobra roll, in radians.
+    pub cobra_turn: f32,
+    /// Smallest sustained heading change for a helix, in radians.
+    pub helix_turn: f32,
+    /// Smallest bank held throughout a helix, in radians.
+    pub helix_bank: f32,
+}
+
+impl ElementConfig {
+    pub const DEFAULT: Self = Self::new(FRAC_PI_2, 30.0, 0.75 * PI, 1.5 * PI, 0.35);
+
+    pub const fn new(
+        inversion_threshold: f32,
+        cobra_gap: f32,
+        cobra_turn: f32,
+        helix_turn: f32,
+        helix_bank: f32,
+    ) -> Self {
+        Self {
+            inversion_threshold,
+            cobra_gap,
+            cobra_turn,
+            helix_turn,
+            helix_bank,
+        }
+    }
+}
+
+impl Default for ElementConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ElementKind {
+    VerticalLoop,
+    ZeroGRoll,
+    Corkscrew,
+    CobraRoll,
+    Helix,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Element {
+    pub kind: ElementKind,
+    pub start_index: usize,
+    /// Exclusive.
+    pub end_index: usize,
+    pub start_arc: f32,
+    pub end_arc: f32,
+    pub inversions: u32,
+}
+
+/// Angle between the rider's up vector (`-normal`) and world up.
+fn tilt(point: &Point) -> f32 {
+    (-point.normal.y).clamp(-1.0, 1.0).acos()
+}
+
+pub fn inversions(points: &[Point], config: &ElementConfig) -> Vec<Range<usize>> {
+    spans(points.len(), |i| tilt(&points[i]) > config.inversion_threshold)
+}
+
+pub fn count_inversions(points: &[Point], config: &ElementConfig) -> usize {
+    inversions(points, config).len()
+}
+
+/// Grows an inversion span out to where the rider is within 45° of upright,
+/// so the whole rotation into and out of the inversion is measured.
+fn widen(points: &[Point], span: &Range<usize>) -> Range<usize> {
+    let mut start = span.start;
+    while start > 0 && tilt(&points[start - 1]) > FRAC_PI_4 {
+        start -= 1;
+    }
+    let mut end = span.end;
+    while end < points.len() && tilt(&points[end]) > FRAC_PI_4 {
+        end += 1;
+    }
+    start..end
+}
+
+fn classify_inversion(points: &[Point], window: &Range<usize>) -> ElementKind {
+    let mut roll_total = 0.0f32;
+    let mut turn_total = 0.0f32;
+    for i in window.start.max(1)..window.end {
+        let (pitch, yaw, roll) = local_rotation(&points[i - 1], &points[i]);
+        roll_total += roll;
+        turn_total += (pitch * pitch + yaw * yaw).sqrt();
+    }
+    let roll_total = roll_total.abs();
+
+    if turn_total > 2.0 * roll_total {
+        ElementKind::VerticalLoop
+    } else if roll_total > 2.0 * turn_total {
+        ElementKind::ZeroGRoll
+    } else {
+        ElementKind::Corkscrew
+    }
+}
+
+fn element(points: &[Point], kind: ElementKind, range: Range<usize>, inversions: u32) -> Element {
+    Element {
+        kind,
+        start_index: range.start,
+        end_index: range.end,
+        start_arc: points[range.start].heart_arc,
+        end_arc: points[range.end - 1].heart_arc,
+        inversions,
+    }
+}
+
+fn helices(points: &[Point], config: &ElementConfig) -> Vec<Element> {
+    let mut result = Vec::new();
+    let mut start = 0;
+    let mut turn = 0.0f32;
+
+    let close = |start: usize, end: usize, turn: f32, result: &mut Vec<Element>| {
+        if turn.abs() >= config.helix_turn {
+            result.push(element(points, ElementKind::Helix, start..end, 0));
+        }
+    };
+
+    for i in 1..points.len() {
+        let point = &points[i];
+        let banked =
+            tilt(point) <= config.inversion_threshold && point.roll().abs() >= config.helix_bank;
+        let step = sim::wrap_angle(point.frame().yaw() - points[i - 1].frame().yaw());
+        let continues = banked && (turn == 0.0 || step * turn >= 0.0);
+
+        if continues {
+            if turn == 0.0 {
+                start = i - 1;
+            }
+            turn += step;
+        } else {
+            close(start, i, turn, &mut result);
+            turn = 0.0;
+            if banked {
+                start = i - 1;
+                turn = step;
+            }
+        }
+    }
+    close(start, points.len(), turn, &mut result);
+
+    result
+}
+
+/// Labels inversions and helices along a path, ordered by start index.
+///
+/// Each inversion is classified by how the frame rotates while the rider is
+/// more than 45° from upright: mostly pitch and yaw is a vertical loop, mostly
+/// roll about the heart line is a zero-g roll, and a mix is a corkscrew. Two
+/// neighbouring inversions that together reverse the heading form a cobra roll.
+pub fn recognize(points: &[Point], config: &ElementConfig) -> Vec<Element> {
+    let windows: Vec<Range<usize>> = inversions(points, config)
+        .iter()
+        .map(|span| widen(points, span))
+        .collect();
+
+    let mut result = Vec::new();
+    let mut i = 0;
+    while i < windows.len() {
+        let window = windows[i].clone();
+
+        if let Some(next) = windows.get(i + 1) {
+            let gap = points[next.start].heart_arc - points[window.end - 1].heart_arc;
+            let entry = points[window.start].frame().yaw();
+            let exit = points[next.end - 1].frame().yaw();
+            if gap <= config.cobra_gap && sim::wrap_angle(exit - entry).abs() >= config.cobra_turn {
+                result.push(element(points, ElementKind::CobraRoll, window.start..next.end, 2));
+                i += 2;
+                continue;
+            }
+        }
+
+        let kind = classify_inversion(points, &window);
+        result.push(element(points, kind, window, 1));
+        i += 1;
+    }
+
+    result.extend(helices(points, config));
+    result.sort_by_key(|e| e.start_index);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kexedit_core::{Float3, Frame};
+
+    const STEP: f32 = 0.2;
+
+    /// Path of `STEP`-spaced points from per-step local `(pitch, yaw, roll)` turns.
+    fn path(turns: &[(f32, f32, f32)]) -> Vec<Point> {
+        let mut frame = Frame::DEFAULT;
+        let mut position = Float3::new(0.0, 30.0, 0.0);
+        let mut arc = 0.0;
+        let mut result = vec![point(frame, position, arc)];
+        for &(pitch, yaw, roll) in turns {
+            frame = frame.rotate_around(frame.lateral, pitch);
+            frame = frame.rotate_around(frame.normal, yaw);
+            frame = frame.rotate_around(frame.direction, roll);
+            position = position + frame.direction * STEP;
+            arc += STEP;
+            result.push(point(frame, position, arc));
+        }
+        result
+    }
+
+    fn point(frame: Frame, position: Float3, arc: f32) -> Point {
+        let mut point = Point::DEFAULT;
+        point.heart_position = position;
+        point.direction = frame.direction;
+        point.normal = frame.normal;
+        point.lateral = frame.lateral;
+        point.heart_arc = arc;
+        point
+    }
+
+    fn repeat(turn: (f32, f32, f32), total: f32, steps: usize) -> Vec<(f32, f32, f32)> {
+        let scale = total / steps as f32;
+        vec![(turn.0 * scale, turn.1 * scale, turn.2 * scale); steps]
+    }
+
+    fn straight(steps: usize) -> Vec<(f32, f32, f32)> {
+        vec![(0.0, 0.0, 0.0); steps]
+    }
+
+    fn kinds(elements: &[Element]) -> Vec<ElementKind> {
+        elements.iter().map(|e| e.kind).collect()
+    }
+
+    #[test]
+    fn straight_track_has_no_elements() {
+        let points = path(&straight(100));
+
+        assert_eq!(count_inversions(&points, &ElementConfig::DEFAULT), 0);
+        assert!(recognize(&points, &ElementConfig::DEFAULT).is_empty());
+    }
+
+    #[test]
+    fn vertical_loop() {
+        let turns = [straight(20), repeat((1.0, 0.0, 0.0), 2.0 * PI, 200), straight(20)].concat();
+        let points = path(&turns);
+
+        let elements = recognize(&points, &ElementConfig::DEFAULT);
+
+        assert_eq!(kinds(&elements), vec![ElementKind::VerticalLoop]);
+        assert_eq!(elements[0].inversions, 1);
+        assert!(elements[0].start_arc < elements[0].end_arc);
+    }
+
+    #[test]
+    fn zero_g_roll() {
+        let turns = [straight(20), repeat((0.0, 0.0, 1.0), 2.0 * PI, 100), straight(20)].concat();
+        let points = path(&turns);
+
+        assert_eq!(kinds(&recognize(&points, &ElementConfig::DEFAULT)), vec![ElementKind::ZeroGRoll]);
+    }
+
+    #[test]
+    fn corkscrew() {
+        let turns = [straight(20), repeat((1.0, 0.0, 1.0), 2.0 * PI / 2.0f32.sqrt(), 200), straight(20)].concat();
+        let points = path(&turns);
+
+        assert_eq!(kinds(&recognize(&points, &ElementConfig::DEFAULT)), vec![ElementKind::Corkscrew]);
+    }
+
+    #[test]
+    fn cobra_roll_pairs_inversions_that_reverse_heading() {
+        let half = [
+            repeat((0.0, 0.0, 1.0), PI, 60),
+            repeat((0.0, 1.0, 0.0), FRAC_PI_2, 60),
+            repeat((0.0, 0.0, 1.0), PI, 60),
+        ]
+        .concat();
+        let turns = [straight(20), half.clone(), straight(20), half, straight(20)].concat();
+        let points = path(&turns);
+
+        let elements = recognize(&points, &ElementConfig::DEFAULT);
+
+        assert_eq!(count_inversions(&points, &ElementConfig::DEFAULT), 2);
+        assert_eq!(kinds(&elements), vec![ElementKind::CobraRoll]);
+        assert_eq!(elements[0].inversions, 2);
+    }
+
+    #[test]
+    fn distant_inversions_are_not_a_cobra_roll() {
+        let half = [
+            repeat((0.0, 0.0, 1.0), PI, 60),
+            repeat((0.0, 1.0, 0.0), FRAC_PI_2, 60),
+            repeat((0.0, 0.0, 1.0), PI, 60),
+        ]
+        .concat();
+        let turns = [straight(20), half.clone(), straight(400), half, straight(20)].concat();
+        let points = path(&turns);
+
+        let elements = recognize(&points, &ElementConfig::DEFAULT);
+
+        assert_eq!(kinds(&elements), vec![ElementKind::ZeroGRoll, ElementKind::ZeroGRoll]);
+    }
+
+    #[test]
+    fn banked_helix() {
+        let mut frame = Frame::DEFAULT.with_roll(0.6);
+        let mut position = Float3::new(0.0, 30.0, 0.0);
+        let mut points = Vec::new();
+        for i in 0..400 {
+            points.push(point(frame, position, i as f32 * STEP));
+            frame = frame.with_yaw(2.0 * PI / 300.0);
+            position = position + frame.direction * STEP;
+        }
+
+        let elements = recognize(&points, &ElementConfig::DEFAULT);
+
+        assert_eq!(kinds(&elements), vec![ElementKind::Helix]);
+        assert_eq!(elements[0].inversions, 0);
+    }
+
+    #[test]
+    fn flat_turn_is_not_a_helix() {
+        let mut frame = Frame::DEFAULT;
+        let mut points = Vec::new();
+        for i in 0..400 {
+            points.push(point(frame, Float3::new(0.0, 30.0, 0.0), i as f32 * STEP));
+            frame = frame.with_yaw(2.0 * PI / 300.0);
+        }
+
+        assert!(recognize(&points, &ElementConfig::DEFAULT).is_empty());
+    }
+}
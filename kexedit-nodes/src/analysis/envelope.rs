@@ -0,0 +1,461 @@
+use std::fmt;
+
+use kexedit_core::{sim, Point};
+
+use super::spans;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EnvelopeAxis {
+    Normal,
+    Lateral,
+    Longitudinal,
+    /// Magnitude of the combined normal, lateral and longitudinal acceleration.
+    Resultant,
+    /// Lateral magnitude against a limit that depends on normal force; see
+    /// `CombinedLimit`.
+    Combined,
+}
+
+impl EnvelopeAxis {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "normal" => Some(Self::Normal),
+            "lateral" => Some(Self::Lateral),
+            "longitudinal" => Some(Self::Longitudinal),
+            "resultant" => Some(Self::Resultant),
+            "combined" => Some(Self::Combined),
+            _ => None,
+        }
+    }
+
+    fn value(self, point: &Point) -> f32 {
+        match self {
+            Self::Normal => point.normal_force,
+            Self::Lateral => point.lateral_force,
+            Self::Longitudinal => point.longitudinal_force,
+            Self::Resultant => (point.normal_force * point.normal_force
+                + point.lateral_force * point.lateral_force
+                + point.longitudinal_force * point.longitudinal_force)
+                .sqrt(),
+            Self::Combined => point.lateral_force.abs(),
+        }
+    }
+}
+
+/// One row of an envelope table: accelerations on `axis` outside `[min, max]`
+/// may last at most `duration` seconds.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct EnvelopeLimit {
+    pub axis: EnvelopeAxis,
+    pub duration: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+impl EnvelopeLimit {
+    pub const fn new(axis: EnvelopeAxis, duration: f32, min: f32, max: f32) -> Self {
+        Self {
+            axis,
+            duration,
+            min,
+            max,
+        }
+    }
+}
+
+/// Lateral limit that varies with normal force, for zones where the permitted
+/// lateral acceleration tightens as vertical acceleration drops. A lateral
+/// magnitude above the limit at the current normal force may last at most
+/// `duration` seconds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CombinedLimit {
+    pub duration: f32,
+    /// `(normal, max |lateral|)` breakpoints in increasing normal order,
+    /// interpolated linearly and held beyond the first and last.
+    pub points: Vec<(f32, f32)>,
+}
+
+impl CombinedLimit {
+    pub fn new(duration: f32, mut points: Vec<(f32, f32)>) -> Self {
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { duration, points }
+    }
+
+    /// Largest lateral magnitude allowed at `normal`.
+    pub fn max_lateral(&self, normal: f32) -> f32 {
+        let Some(&(first_normal, first_lateral)) = self.points.first() else {
+            return f32::INFINITY;
+        };
+        if normal <= first_normal {
+            return first_lateral;
+        }
+        let i = self.points.partition_point(|&(n, _)| n <= normal);
+        if i == self.points.len() {
+            return self.points[i - 1].1;
+        }
+        let (n0, l0) = self.points[i - 1];
+        let (n1, l1) = self.points[i];
+        l0 + (l1 - l0) * (normal - n0) / (n1 - n0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EnvelopeError {
+    FieldCount { line: usize, found: usize },
+    UnknownAxis { line: usize, name: String },
+    InvalidNumber { line: usize, value: String },
+    InvalidRange { line: usize },
+}
+
+impl fmt::Display for EnvelopeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FieldCount { line, found } => {
+                write!(f, "line {}: expected 4 fields (axis, duration, min, max), found {}", line, found)
+            }
+            Self::UnknownAxis { line, name } => write!(f, "line {}: unknown axis '{}'", line, name),
+            Self::InvalidNumber { line, value } => write!(f, "line {}: invalid number '{}'", line, value),
+            Self::InvalidRange { line } => write!(
+                f,
+                "line {}: duration must be non-negative, min must not exceed max and combined lateral limits must be non-negative",
+                line
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EnvelopeError {}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Envelope {
+    pub limits: Vec<EnvelopeLimit>,
+    pub combined: Vec<CombinedLimit>,
+}
+
+impl Envelope {
+    pub fn new(limits: Vec<EnvelopeLimit>) -> Self {
+        Self {
+            limits,
+            combined: Vec::new(),
+        }
+    }
+
+    pub fn with_combined(mut self, limit: CombinedLimit) -> Self {
+        self.combined.push(limit);
+        self
+    }
+
+    /// Parses an envelope table with one `axis, duration, min, max` row per line.
+    ///
+    /// Rows on the `combined` axis read `combined, duration, normal, lateral`
+    /// instead: each is one breakpoint of a `CombinedLimit`, and rows sharing a
+    /// duration form one limit.
+    ///
+    /// Fields may be separated by commas or whitespace. Blank lines and lines
+    /// starting with `#` are ignored, as is a header row starting with `axis`.
+    pub fn parse(text: &str) -> Result<Self, EnvelopeError> {
+        let mut limits = Vec::new();
+        let mut combined: Vec<CombinedLimit> = Vec::new();
+        for (index, raw) in text.lines().enumerate() {
+            let line = index + 1;
+            let trimmed = raw.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = trimmed
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|f| !f.is_empty())
+                .collect();
+            if fields.first().is_some_and(|f| f.eq_ignore_ascii_case("axis")) {
+                continue;
+            }
+            if fields.len() != 4 {
+                return Err(EnvelopeError::FieldCount {
+                    line,
+                    found: fields.len(),
+                });
+            }
+
+            let axis = EnvelopeAxis::parse(fields[0]).ok_or_else(|| EnvelopeError::UnknownAxis {
+                line,
+                name: fields[0].to_string(),
+            })?;
+            let number = |value: &str| {
+                value.parse::<f32>().map_err(|_| EnvelopeError::InvalidNumber {
+                    line,
+                    value: value.to_string(),
+                })
+            };
+            let duration = number(fields[1])?;
+            if axis == EnvelopeAxis::Combined {
+                let normal = number(fields[2])?;
+                let lateral = number(fields[3])?;
+                if duration.is_nan() || duration < 0.0 || normal.is_nan() || lateral.is_nan() || lateral < 0.0 {
+                    return Err(EnvelopeError::InvalidRange { line });
+                }
+                match combined.iter_mut().find(|limit| limit.duration == duration) {
+                    Some(limit) => limit.points.push((normal, lateral)),
+                    None => combined.push(CombinedLimit::new(duration, vec![(normal, lateral)])),
+                }
+                continue;
+            }
+
+            let min = number(fields[2])?;
+            let max = number(fields[3])?;
+            if duration.is_nan() || duration < 0.0 || min.is_nan() || max.is_nan() || min > max {
+                return Err(EnvelopeError::InvalidRange { line });
+            }
+
+            limits.push(EnvelopeLimit::new(axis, duration, min, max));
+        }
+        let combined = combined
+            .into_iter()
+            .map(|limit| CombinedLimit::new(limit.duration, limit.points))
+            .collect();
+        Ok(Self { limits, combined })
+    }
+}
+
+/// A stretch of points where one limit row was exceeded for longer than allowed.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Violation {
+    /// Index into `Envelope::limits`, or into `Envelope::combined` when `axis`
+    /// is `Combined`.
+    pub limit: usize,
+    pub axis: EnvelopeAxis,
+    pub start_index: usize,
+    /// Exclusive.
+    pub end_index: usize,
+    pub start_arc: f32,
+    pub end_arc: f32,
+    /// Seconds spent outside the limit.
+    pub duration: f32,
+    /// Value furthest outside the limit; the signed lateral force for
+    /// combined limits.
+    pub peak: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentReport {
+    pub segment: usize,
+    pub violations: Vec<Violation>,
+}
+
+impl SegmentReport {
+    pub fn passed(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// Checks a single path against every row and combined limit of `envelope`.
+pub fn check_path(points: &[Point], envelope: &Envelope) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    for (limit_index, limit) in envelope.limits.iter().enumerate() {
+        let values: Vec<f32> = points.iter().map(|p| limit.axis.value(p)).collect();
+        let excess: Vec<f32> = values.iter().map(|&v| (v - limit.max).max(limit.min - v)).collect();
+        push_violations(&mut violations, points, limit_index, limit.axis, limit.duration, &values, &excess);
+    }
+    for (limit_index, limit) in envelope.combined.iter().enumerate() {
+        let values: Vec<f32> = points.iter().map(|p| p.lateral_force).collect();
+        let excess: Vec<f32> = points
+            .iter()
+            .map(|p| p.lateral_force.abs() - limit.max_lateral(p.normal_force))
+            .collect();
+        push_violations(&mut violations, points, limit_index, EnvelopeAxis::Combined, limit.duration, &values, &excess);
+    }
+    violations.sort_by_key(|v| (v.start_index, v.axis == EnvelopeAxis::Combined, v.limit));
+    violations
+}
+
+/// Reports each run of positive `excess` lasting longer than `allowed` seconds,
+/// with the value at the largest excess as its peak.
+fn push_violations(
+    violations: &mut Vec<Violation>,
+    points: &[Point],
+    limit: usize,
+    axis: EnvelopeAxis,
+    allowed: f32,
+    values: &[f32],
+    excess: &[f32],
+) {
+    for span in spans(excess.len(), |i| excess[i] > 0.0) {
+        let duration = span.len() as f32 * sim::DT;
+        if duration <= allowed + sim::EPSILON {
+            continue;
+        }
+
+        let worst = span.clone().fold(span.start, |acc, i| if excess[i] > excess[acc] { i } else { acc });
+        violations.push(Violation {
+            limit,
+            axis,
+            start_index: span.start,
+            end_index: span.end,
+            start_arc: points[span.start].heart_arc,
+            end_arc: points[span.end - 1].heart_arc,
+            duration,
+            peak: values[worst],
+        });
+    }
+}
+
+/// Checks each segment of a ride independently, one report per segment.
+pub fn check(segments: &[&[Point]], envelope: &Envelope) -> Vec<SegmentReport> {
+    segments
+        .iter()
+        .enumerate()
+        .map(|(segment, points)| SegmentReport {
+            segment,
+            violations: check_path(points, envelope),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn path(normal_forces: &[f32]) -> Vec<Point> {
+        normal_forces
+            .iter()
+            .enumerate()
+            .map(|(i, &n)| {
+                let mut point = Point::DEFAULT.with_forces(n, 0.0);
+                point.heart_arc = i as f32;
+                point
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parse_reads_rows_and_skips_comments() {
+        let text = "# sustained limits\naxis,duration,min,max\nnormal, 0.0, -2.0, 6.0\nLateral 1.5 -1.2 1.2\n\n";
+
+        let envelope = Envelope::parse(text).unwrap();
+
+        assert_eq!(
+            envelope.limits,
+            vec![
+                EnvelopeLimit::new(EnvelopeAxis::Normal, 0.0, -2.0, 6.0),
+                EnvelopeLimit::new(EnvelopeAxis::Lateral, 1.5, -1.2, 1.2),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_reports_line_of_bad_row() {
+        assert_eq!(
+            Envelope::parse("normal,0,-2,6\nvertical,0,-1,1"),
+            Err(EnvelopeError::UnknownAxis {
+                line: 2,
+                name: "vertical".to_string()
+            })
+        );
+        assert_eq!(
+            Envelope::parse("normal,0,-2"),
+            Err(EnvelopeError::FieldCount { line: 1, found: 3 })
+        );
+        assert_eq!(
+            Envelope::parse("normal,x,-2,6"),
+            Err(EnvelopeError::InvalidNumber {
+                line: 1,
+                value: "x".to_string()
+            })
+        );
+        assert_eq!(
+            Envelope::parse("normal,0,6,-2"),
+            Err(EnvelopeError::InvalidRange { line: 1 })
+        );
+    }
+
+    #[test]
+    fn short_excursion_within_allowed_duration_passes() {
+        let mut forces = vec![1.0; 100];
+        for f in &mut forces[40..45] {
+            *f = 5.0;
+        }
+        let envelope = Envelope::new(vec![EnvelopeLimit::new(EnvelopeAxis::Normal, 0.1, -1.0, 4.0)]);
+
+        assert!(check_path(&path(&forces), &envelope).is_empty());
+    }
+
+    #[test]
+    fn sustained_excursion_is_reported() {
+        let mut forces = vec![1.0; 100];
+        for (i, f) in forces[40..70].iter_mut().enumerate() {
+            *f = if i == 10 { 5.5 } else { 5.0 };
+        }
+        let envelope = Envelope::new(vec![EnvelopeLimit::new(EnvelopeAxis::Normal, 0.1, -1.0, 4.0)]);
+
+        let violations = check_path(&path(&forces), &envelope);
+
+        assert_eq!(violations.len(), 1);
+        let v = violations[0];
+        assert_eq!((v.start_index, v.end_index), (40, 70));
+        assert_relative_eq!(v.duration, 0.3, epsilon = 1e-5);
+        assert_relative_eq!(v.peak, 5.5);
+        assert_relative_eq!(v.start_arc, 40.0);
+        assert_relative_eq!(v.end_arc, 69.0);
+    }
+
+    #[test]
+    fn resultant_combines_axes() {
+        let points = vec![Point::DEFAULT.with_forces(3.0, 4.0); 10];
+        let envelope = Envelope::new(vec![EnvelopeLimit::new(EnvelopeAxis::Resultant, 0.0, 0.0, 4.5)]);
+
+        let violations = check_path(&points, &envelope);
+
+        assert_eq!(violations.len(), 1);
+        assert_relative_eq!(violations[0].peak, 5.0);
+    }
+
+    #[test]
+    fn parse_groups_combined_rows_by_duration() {
+        let text = "normal,0,-2,6\ncombined,0.5,1.0,1.2\ncombined,0.5,0.0,0.6\ncombined,0,0.0,1.0\n";
+
+        let envelope = Envelope::parse(text).unwrap();
+
+        assert_eq!(envelope.limits.len(), 1);
+        assert_eq!(
+            envelope.combined,
+            vec![
+                CombinedLimit::new(0.5, vec![(0.0, 0.6), (1.0, 1.2)]),
+                CombinedLimit::new(0.0, vec![(0.0, 1.0)]),
+            ]
+        );
+        assert_eq!(Envelope::parse("combined,0,1,-0.5"), Err(EnvelopeError::InvalidRange { line: 1 }));
+    }
+
+    #[test]
+    fn combined_limit_tightens_lateral_as_normal_drops() {
+        let limit = CombinedLimit::new(0.0, vec![(0.0, 0.5), (1.0, 1.5)]);
+        let point = |normal, lateral| Point::DEFAULT.with_forces(normal, lateral);
+        let envelope = Envelope::default().with_combined(limit.clone());
+
+        assert_relative_eq!(limit.max_lateral(0.5), 1.0);
+        assert_relative_eq!(limit.max_lateral(-1.0), 0.5);
+        assert_relative_eq!(limit.max_lateral(3.0), 1.5);
+        assert!(check_path(&[point(1.0, -0.8); 5], &envelope).is_empty());
+
+        let violations = check_path(&[point(0.2, -0.8); 5], &envelope);
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].axis, EnvelopeAxis::Combined);
+        assert_relative_eq!(violations[0].peak, -0.8);
+    }
+
+    #[test]
+    fn check_reports_each_segment() {
+        let calm = path(&[1.0; 20]);
+        let harsh = path(&[-2.0; 20]);
+        let envelope = Envelope::new(vec![EnvelopeLimit::new(EnvelopeAxis::Normal, 0.05, -1.5, 5.0)]);
+
+        let reports = check(&[&calm, &harsh], &envelope);
+
+        assert_eq!(reports.len(), 2);
+        assert!(reports[0].passed());
+        assert!(!reports[1].passed());
+        assert_eq!(reports[1].segment, 1);
+    }
+}
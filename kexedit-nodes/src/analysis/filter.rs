@@ -0,0 +1,205 @@
+use std::f32::consts::PI;
+
+use kexedit_core::{sim, Point};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LowPass {
+    /// Cutoff frequency in Hz, clamped below the Nyquist frequency of `sim::HZ`.
+    pub cutoff: f32,
+    /// Filter order, rounded up to the next even number.
+    pub order: u32,
+    /// Run the filter forwards and backwards, cancelling phase lag and doubling the order.
+    pub zero_phase: bool,
+}
+
+impl LowPass {
+    pub const fn new(cutoff: f32, order: u32, zero_phase: bool) -> Self {
+        Self {
+            cutoff,
+            order,
+            zero_phase,
+        }
+    }
+
+    fn sections(&self) -> Vec<Biquad> {
+        let cutoff = self.cutoff.min(0.49 * sim::HZ);
+        let pairs = self.order.max(1).div_ceil(2);
+        let poles = 2 * pairs;
+        (0..pairs)
+            .map(|k| {
+                let q = 1.0 / (2.0 * ((2 * k + 1) as f32 * PI / (2 * poles) as f32).cos());
+                Biquad::low_pass(cutoff, q)
+            })
+            .collect()
+    }
+
+    pub fn apply(&self, values: &[f32]) -> Vec<f32> {
+        if values.is_empty() || self.cutoff.is_nan() || self.cutoff <= 0.0 {
+            return values.to_vec();
+        }
+
+        let sections = self.sections();
+        let mut result = values.to_vec();
+        run(&sections, &mut result);
+        if self.zero_phase {
+            result.reverse();
+            run(&sections, &mut result);
+            result.reverse();
+        }
+        result
+    }
+}
+
+/// Second-order section in transposed direct form II.
+#[derive(Debug, Copy, Clone)]
+struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+}
+
+impl Biquad {
+    fn low_pass(cutoff: f32, q: f32) -> Self {
+        let omega = 2.0 * PI * cutoff / sim::HZ;
+        let (sin, cos) = omega.sin_cos();
+        let alpha = sin / (2.0 * q);
+        let a0 = 1.0 + alpha;
+        let b1 = (1.0 - cos) / a0;
+        Self {
+            b0: b1 / 2.0,
+            b1,
+            b2: b1 / 2.0,
+            a1: -2.0 * cos / a0,
+            a2: (1.0 - alpha) / a0,
+        }
+    }
+
+    /// Filters in place, starting from the steady state of the first sample so
+    /// a non-zero signal does not ramp up from zero.
+    fn process(&self, values: &mut [f32]) {
+        let first = values[0];
+        let mut z1 = (1.0 - self.b0) * first;
+        let mut z2 = (self.b2 - self.a2) * first;
+        for v in values.iter_mut() {
+            let x = *v;
+            let y = self.b0 * x + z1;
+            z1 = self.b1 * x - self.a1 * y + z2;
+            z2 = self.b2 * x - self.a2 * y;
+            *v = y;
+        }
+    }
+}
+
+fn run(sections: &[Biquad], values: &mut [f32]) {
+    for section in sections {
+        section.process(values);
+    }
+}
+
+/// Returns `points` with normal, lateral and longitudinal forces low-pass
+/// filtered; geometry and velocity are unchanged.
+pub fn filter_forces(points: &[Point], filter: &LowPass) -> Vec<Point> {
+    let normal = filter.apply(&points.iter().map(|p| p.normal_force).collect::<Vec<_>>());
+    let lateral = filter.apply(&points.iter().map(|p| p.lateral_force).collect::<Vec<_>>());
+    let longitudinal = filter.apply(&points.iter().map(|p| p.longitudinal_force).collect::<Vec<_>>());
+
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            p.with_forces(normal[i], lateral[i])
+                .with_longitudinal_force(longitudinal[i])
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn sine(frequency: f32, count: usize) -> Vec<f32> {
+        (0..count)
+            .map(|i| (2.0 * PI * frequency * i as f32 / sim::HZ).sin())
+            .collect()
+    }
+
+    fn amplitude(values: &[f32]) -> f32 {
+        values.iter().fold(0.0f32, |acc, v| acc.max(v.abs()))
+    }
+
+    #[test]
+    fn constant_signal_is_unchanged() {
+        let values = vec![1.5; 200];
+        let filtered = LowPass::new(5.0, 4, false).apply(&values);
+
+        for v in filtered {
+            assert_relative_eq!(v, 1.5, epsilon = 1e-4);
+        }
+    }
+
+    #[test]
+    fn attenuates_above_cutoff_and_passes_below() {
+        let filter = LowPass::new(5.0, 4, true);
+
+        let low = filter.apply(&sine(0.5, 1000));
+        let high = filter.apply(&sine(30.0, 1000));
+
+        assert!(amplitude(&low[200..800]) > 0.95);
+        assert!(amplitude(&high[200..800]) < 0.01);
+    }
+
+    #[test]
+    fn cutoff_is_half_power_point() {
+        let filter = LowPass::new(4.0, 2, false);
+
+        let filtered = filter.apply(&sine(4.0, 2000));
+
+        assert_relative_eq!(amplitude(&filtered[1000..]), 0.5f32.sqrt(), epsilon = 2e-2);
+    }
+
+    #[test]
+    fn zero_phase_keeps_peak_position() {
+        let values: Vec<f32> = (0..300)
+            .map(|i| {
+                let t = (i as f32 - 150.0) / 20.0;
+                (-t * t).exp()
+            })
+            .collect();
+        let peak_index = |v: &[f32]| {
+            v.iter()
+                .enumerate()
+                .fold((0, f32::MIN), |acc, (i, &x)| if x > acc.1 { (i, x) } else { acc })
+                .0
+        };
+
+        let causal = LowPass::new(3.0, 4, false).apply(&values);
+        let zero_phase = LowPass::new(3.0, 4, true).apply(&values);
+
+        assert!(peak_index(&causal) > 150);
+        assert_eq!(peak_index(&zero_phase), 150);
+    }
+
+    #[test]
+    fn non_positive_cutoff_disables_filter() {
+        let values = sine(10.0, 50);
+        assert_eq!(LowPass::new(0.0, 2, true).apply(&values), values);
+    }
+
+    #[test]
+    fn filter_forces_smooths_step_and_keeps_geometry() {
+        let points: Vec<Point> = (0..100)
+            .map(|i| Point::DEFAULT.with_forces(if i < 50 { 1.0 } else { 3.0 }, 0.0))
+            .collect();
+
+        let filtered = filter_forces(&points, &LowPass::new(5.0, 2, true));
+
+        assert_eq!(filtered.len(), points.len());
+        assert!(filtered[50].normal_force > 1.0 && filtered[50].normal_force < 3.0);
+        assert!(filtered[49].normal_force > 1.0);
+        assert_eq!(filtered[50].heart_position, points[50].heart_position);
+        assert_eq!(filtered[50].velocity, points[50].velocity);
+    }
+}
@@ -0,0 +1,250 @@
+use kexedit_core::{sim, Point};
+
+use super::{derivative, local_rotation, moving_average, spans};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct JerkConfig {
+    /// Width of the centred moving average applied before differentiating, in seconds.
+    pub smoothing: f32,
+    /// Normal jerk limit in G/s.
+    pub normal_jerk_limit: f32,
+    /// Lateral jerk limit in G/s.
+    pub lateral_jerk_limit: f32,
+    /// Roll acceleration limit in rad/s².
+    pub roll_acceleration_limit: f32,
+}
+
+impl JerkConfig {
+    pub const fn new(
+        smoothing: f32,
+        normal_jerk_limit: f32,
+        lateral_jerk_limit: f32,
+        roll_acceleration_limit: f32,
+    ) -> Self {
+        Self {
+            smoothing,
+            normal_jerk_limit,
+            lateral_jerk_limit,
+            roll_acceleration_limit,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum JerkChannel {
+    NormalJerk,
+    LateralJerk,
+    RollAcceleration,
+}
+
+/// A window where a channel's magnitude stays above its limit.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Exceedance {
+    pub channel: JerkChannel,
+    pub start_index: usize,
+    /// Exclusive.
+    pub end_index: usize,
+    pub start_arc: f32,
+    pub end_arc: f32,
+    /// Signed value with the largest magnitude inside the window.
+    pub peak: f32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct JerkAnalysis {
+    /// G/s per point.
+    pub normal_jerk: Vec<f32>,
+    /// G/s per point.
+    pub lateral_jerk: Vec<f32>,
+    /// Roll rate about the track direction from frame changes, rad/s per point.
+    pub roll_rate: Vec<f32>,
+    /// rad/s² per point.
+    pub roll_acceleration: Vec<f32>,
+    pub exceedances: Vec<Exceedance>,
+}
+
+/// Roll rate is measured from the frame change between points rather than read
+/// from `Point::roll_speed`, which is per metre in distance sections and is
+/// carried over unchanged from the anchor by copy-path sections. Where the
+/// stored roll speed is per second the two agree.
+pub fn analyze(points: &[Point], config: &JerkConfig) -> JerkAnalysis {
+    if points.is_empty() {
+        return JerkAnalysis::default();
+    }
+
+    let normal: Vec<f32> = points.iter().map(|p| p.normal_force).collect();
+    let lateral: Vec<f32> = points.iter().map(|p| p.lateral_force).collect();
+    let mut roll_rate = Vec::with_capacity(points.len());
+    roll_rate.push(0.0);
+    for pair in points.windows(2) {
+        let (_, _, roll) = local_rotation(&pair[0], &pair[1]);
+        roll_rate.push(roll * sim::HZ);
+    }
+
+    let roll_rate = moving_average(&roll_rate, config.smoothing);
+    let normal_jerk = derivative(&moving_average(&normal, config.smoothing));
+    let lateral_jerk = derivative(&moving_average(&lateral, config.smoothing));
+    let roll_acceleration = derivative(&roll_rate);
+
+    let mut exceedances = Vec::new();
+    for (channel, values, limit) in [
+        (JerkChannel::NormalJerk, &normal_jerk, config.normal_jerk_limit),
+        (JerkChannel::LateralJerk, &lateral_jerk, config.lateral_jerk_limit),
+        (JerkChannel::RollAcceleration, &roll_acceleration, config.roll_acceleration_limit),
+    ] {
+        for span in spans(values.len(), |i| values[i].abs() > limit) {
+            let peak = values[span.clone()]
+                .iter()
+                .copied()
+                .fold(0.0f32, |acc, v| if v.abs() > acc.abs() { v } else { acc });
+            exceedances.push(Exceedance {
+                channel,
+                start_index: span.start,
+                end_index: span.end,
+                start_arc: points[span.start].heart_arc,
+                end_arc: points[span.end - 1].heart_arc,
+                peak,
+            });
+        }
+    }
+    exceedances.sort_by_key(|e| e.start_index);
+
+    JerkAnalysis {
+        normal_jerk,
+        lateral_jerk,
+        roll_rate,
+        roll_acceleration,
+        exceedances,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::roll::RollChannel;
+    use crate::{force, DurationType, IterationConfig};
+    use approx::assert_relative_eq;
+    use kexedit_core::{InterpolationType, Keyframe};
+
+    fn linear(time: f32, value: f32) -> Keyframe {
+        Keyframe::new(
+            time,
+            value,
+            InterpolationType::Linear,
+            InterpolationType::Linear,
+            0.0,
+            0.0,
+            1.0 / 3.0,
+            1.0 / 3.0,
+        )
+    }
+
+    fn build(duration: f32, normal_force: &[Keyframe], roll_speed: &[Keyframe]) -> Vec<Point> {
+        let config = IterationConfig::new(duration, DurationType::Time);
+        force::build(
+            &Point::DEFAULT,
+            &config,
+            false,
+            RollChannel::Speed,
+            roll_speed,
+            normal_force,
+            &[],
+            &[],
+            &[],
+            &[],
+            &[],
+            1.1,
+            0.0,
+            0.0,
+        )
+    }
+
+    #[test]
+    fn linear_force_ramp_has_constant_jerk() {
+        let points = build(0.5, &[linear(0.0, 1.0), linear(0.5, 1.2)], &[]);
+        let config = JerkConfig::new(0.0, 10.0, 10.0, 10.0);
+
+        let analysis = analyze(&points, &config);
+
+        for &jerk in &analysis.normal_jerk[5..45] {
+            assert_relative_eq!(jerk, 0.4, epsilon = 5e-2);
+        }
+        assert!(analysis.exceedances.is_empty());
+    }
+
+    #[test]
+    fn constant_roll_speed_has_matching_roll_rate() {
+        let points = build(2.0, &[], &[linear(0.0, 1.0), linear(2.0, 1.0)]);
+        let config = JerkConfig::new(0.0, 10.0, 10.0, 10.0);
+
+        let analysis = analyze(&points, &config);
+
+        for &rate in &analysis.roll_rate[10..150] {
+            assert_relative_eq!(rate, 1.0, epsilon = 2e-2);
+        }
+    }
+
+    #[test]
+    fn roll_acceleration_matches_stored_roll_speed() {
+        let points = build(1.0, &[], &[linear(0.0, 0.0), linear(1.0, 2.0)]);
+        let config = JerkConfig::new(0.0, 10.0, 10.0, 10.0);
+
+        let analysis = analyze(&points, &config);
+
+        for i in 2..points.len() {
+            assert_relative_eq!(analysis.roll_rate[i], points[i].roll_speed, epsilon = 2e-2);
+            let stored = (points[i].roll_speed - points[i - 1].roll_speed) * sim::HZ;
+            assert_relative_eq!(analysis.roll_acceleration[i], stored, epsilon = 5e-2);
+        }
+    }
+
+    #[test]
+    fn abrupt_force_step_is_flagged() {
+        let step = [
+            linear(0.0, 1.0),
+            Keyframe::new(
+                0.5,
+                1.0,
+                InterpolationType::Constant,
+                InterpolationType::Constant,
+                0.0,
+                0.0,
+                1.0 / 3.0,
+                1.0 / 3.0,
+            ),
+            linear(0.51, 1.5),
+        ];
+        let points = build(1.0, &step, &[]);
+        let config = JerkConfig::new(0.0, 20.0, 20.0, 20.0);
+
+        let analysis = analyze(&points, &config);
+
+        let normal: Vec<_> = analysis
+            .exceedances
+            .iter()
+            .filter(|e| e.channel == JerkChannel::NormalJerk)
+            .collect();
+        assert_eq!(normal.len(), 1);
+        assert!(normal[0].peak > 20.0);
+        assert!(normal[0].start_arc <= normal[0].end_arc);
+    }
+
+    #[test]
+    fn smoothing_reduces_peak_jerk() {
+        let step = [linear(0.0, 1.0), linear(0.5, 1.0), linear(0.55, 1.5)];
+        let points = build(1.0, &step, &[]);
+
+        let raw = analyze(&points, &JerkConfig::new(0.0, 100.0, 100.0, 100.0));
+        let smoothed = analyze(&points, &JerkConfig::new(0.3, 100.0, 100.0, 100.0));
+
+        let peak = |values: &[f32]| values.iter().fold(0.0f32, |acc, v| acc.max(v.abs()));
+        assert!(peak(&smoothed.normal_jerk) < peak(&raw.normal_jerk));
+    }
+
+    #[test]
+    fn empty_input_returns_empty_analysis() {
+        let analysis = analyze(&[], &JerkConfig::new(0.1, 1.0, 1.0, 1.0));
+        assert!(analysis.normal_jerk.is_empty());
+        assert!(analysis.exceedances.is_empty());
+    }
+}
@@ -0,0 +1,166 @@
+//! Post-build analysis over sequences of built `Point`s.
+//!
+//! Every builder emits one point per simulation step, so consecutive points are
+//! `sim::DT` apart and time derivatives are taken with that spacing.
+
+use std::ops::Range;
+
+use kexedit_core::{sim, Point};
+
+pub mod airtime;
+pub mod elements;
+pub mod envelope;
+pub mod filter;
+pub mod jerk;
+pub mod stats;
+
+/// Rotation from `prev` to `curr` expressed in `prev`'s frame, as
+/// `(pitch, yaw, roll)` in radians.
+///
+/// Pitch is positive when the track turns towards the rider's head, yaw when it
+/// turns towards `lateral`, and roll follows the sign of `Frame::roll`.
+pub(crate) fn local_rotation(prev: &Point, curr: &Point) -> (f32, f32, f32) {
+    let forward = curr.direction.dot(prev.direction);
+    let pitch = (-curr.direction.dot(prev.normal)).atan2(forward);
+    let yaw = curr.direction.dot(prev.lateral).atan2(forward);
+    let roll = (-curr.lateral.dot(prev.normal)).atan2(curr.lateral.dot(prev.lateral));
+    (pitch, yaw, roll)
+}
+
+/// Backward difference scaled to per-second units; the first sample is zero.
+pub(crate) fn derivative(values: &[f32]) -> Vec<f32> {
+    let mut result = Vec::with_capacity(values.len());
+    for i in 0..values.len() {
+        if i == 0 {
+            result.push(0.0);
+        } else {
+            result.push((values[i] - values[i - 1]) * sim::HZ);
+        }
+    }
+    result
+}
+
+/// Centred moving average over `window` seconds, shrinking at the ends.
+pub(crate) fn moving_average(values: &[f32], window: f32) -> Vec<f32> {
+    let half = ((window * sim::HZ) / 2.0).floor().max(0.0) as usize;
+    if half == 0 {
+        return values.to_vec();
+    }
+
+    let mut prefix = Vec::with_capacity(values.len() + 1);
+    prefix.push(0.0f64);
+    for &v in values {
+        prefix.push(prefix[prefix.len() - 1] + v as f64);
+    }
+
+    (0..values.len())
+        .map(|i| {
+            let start = i.saturating_sub(half);
+            let end = (i + half + 1).min(values.len());
+            ((prefix[end] - prefix[start]) / (end - start) as f64) as f32
+        })
+        .collect()
+}
+
+/// Contiguous index ranges where `predicate` holds.
+pub(crate) fn spans(len: usize, mut predicate: impl FnMut(usize) -> bool) -> Vec<Range<usize>> {
+    let mut result = Vec::new();
+    let mut start = None;
+    for i in 0..len {
+        match (predicate(i), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                result.push(s..i);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        result.push(s..len);
+    }
+    result
+}
+
+/// Consecutive paths as one ride, e.g. the outputs of each node in ride order.
+/// Each later path's anchor duplicates the previous path's last point and is
+/// dropped, and the path's heart arc is shifted to continue from that point.
+pub(crate) fn join_paths(paths: &[&[Point]]) -> Vec<Point> {
+    let mut ride: Vec<Point> = Vec::with_capacity(paths.iter().map(|p| p.len()).sum());
+    for path in paths {
+        let Some(start) = path.first() else {
+            continue;
+        };
+        match ride.last() {
+            None => ride.extend_from_slice(path),
+            Some(end) => {
+                let shift = end.heart_arc - start.heart_arc;
+                ride.extend(path[1..].iter().map(|&point| Point {
+                    heart_arc: point.heart_arc + shift,
+                    ..point
+                }));
+            }
+        }
+    }
+    ride
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use kexedit_core::Frame;
+
+    fn point_with_frame(frame: Frame) -> Point {
+        let mut point = Point::DEFAULT;
+        point.direction = frame.direction;
+        point.normal = frame.normal;
+        point.lateral = frame.lateral;
+        point
+    }
+
+    #[test]
+    fn local_rotation_separates_axes() {
+        let frame = Frame::DEFAULT;
+        let prev = point_with_frame(frame);
+
+        let (pitch, yaw, roll) = local_rotation(&prev, &point_with_frame(frame.with_pitch(0.1)));
+        assert_relative_eq!(pitch, 0.1, epsilon = 1e-5);
+        assert_relative_eq!(yaw, 0.0, epsilon = 1e-5);
+        assert_relative_eq!(roll, 0.0, epsilon = 1e-5);
+
+        let (pitch, _, roll) = local_rotation(&prev, &point_with_frame(frame.with_roll(0.2)));
+        assert_relative_eq!(pitch, 0.0, epsilon = 1e-5);
+        assert_relative_eq!(roll, 0.2, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn join_paths_drops_later_anchors_and_continues_arc() {
+        let arcs = |arcs: &[f32]| -> Vec<Point> {
+            arcs.iter()
+                .map(|&arc| Point { heart_arc: arc, ..Point::DEFAULT })
+                .collect()
+        };
+        let (first, second) = (arcs(&[0.0, 2.0]), arcs(&[0.0, 3.0, 5.0]));
+
+        let ride = join_paths(&[&first, &[], &second]);
+
+        let joined: Vec<f32> = ride.iter().map(|p| p.heart_arc).collect();
+        assert_eq!(joined, [0.0, 2.0, 5.0, 7.0]);
+    }
+
+    #[test]
+    fn moving_average_preserves_constant_signal() {
+        let values = vec![2.0; 50];
+        for v in moving_average(&values, 0.1) {
+            assert_relative_eq!(v, 2.0, epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    fn spans_finds_contiguous_ranges() {
+        let mask = [false, true, true, false, true];
+        let result = spans(mask.len(), |i| mask[i]);
+        assert_eq!(result, vec![1..3, 4..5]);
+    }
+}
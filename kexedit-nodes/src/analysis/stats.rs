@@ -0,0 +1,169 @@
+use kexedit_core::{sim, Point};
+
+use super::airtime::AirtimeThresholds;
+use super::join_paths;
+
+/// An extreme value and where along the ride it occurs.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct Extreme {
+    pub value: f32,
+    /// Heart-line distance from the start of the ride.
+    pub arc: f32,
+    /// Seconds from the start of the ride.
+    pub time: f32,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct RideStats {
+    /// Heart-line length.
+    pub length: f32,
+    /// Seconds.
+    pub duration: f32,
+    pub max_height: Extreme,
+    pub min_height: Extreme,
+    pub max_velocity: Extreme,
+    pub max_normal_force: Extreme,
+    pub min_normal_force: Extreme,
+    pub max_lateral_force: Extreme,
+    pub min_lateral_force: Extreme,
+    /// Seconds below the floater threshold of `AirtimeThresholds::DEFAULT`,
+    /// matching the total reported by `airtime::analyze`.
+    pub airtime: f32,
+}
+
+impl RideStats {
+    pub fn from_path(points: &[Point]) -> Self {
+        let mut stats = Self::default();
+        let Some(start) = points.first() else {
+            return stats;
+        };
+
+        let location = |i: usize, value: f32| Extreme {
+            value,
+            arc: points[i].heart_arc - start.heart_arc,
+            time: i as f32 * sim::DT,
+        };
+        stats.max_height = location(0, start.heart_position.y);
+        stats.min_height = stats.max_height;
+        stats.max_velocity = location(0, start.velocity);
+        stats.max_normal_force = location(0, start.normal_force);
+        stats.min_normal_force = stats.max_normal_force;
+        stats.max_lateral_force = location(0, start.lateral_force);
+        stats.min_lateral_force = stats.max_lateral_force;
+
+        let floater = AirtimeThresholds::DEFAULT.floater;
+        for (i, point) in points.iter().enumerate() {
+            if point.heart_position.y > stats.max_height.value {
+                stats.max_height = location(i, point.heart_position.y);
+            }
+            if point.heart_position.y < stats.min_height.value {
+                stats.min_height = location(i, point.heart_position.y);
+            }
+            if point.velocity > stats.max_velocity.value {
+                stats.max_velocity = location(i, point.velocity);
+            }
+            if point.normal_force > stats.max_normal_force.value {
+                stats.max_normal_force = location(i, point.normal_force);
+            }
+            if point.normal_force < stats.min_normal_force.value {
+                stats.min_normal_force = location(i, point.normal_force);
+            }
+            if point.lateral_force > stats.max_lateral_force.value {
+                stats.max_lateral_force = location(i, point.lateral_force);
+            }
+            if point.lateral_force < stats.min_lateral_force.value {
+                stats.min_lateral_force = location(i, point.lateral_force);
+            }
+            if point.normal_force < floater {
+                stats.airtime += sim::DT;
+            }
+        }
+
+        stats.length = points[points.len() - 1].heart_arc - start.heart_arc;
+        stats.duration = (points.len() - 1) as f32 * sim::DT;
+        stats
+    }
+
+    /// Combines consecutive paths, e.g. the outputs of each node in ride order.
+    ///
+    /// Each path starts at its anchor, which duplicates the previous path's last
+    /// point, so only the first path's anchor is counted.
+    pub fn from_paths(paths: &[&[Point]]) -> Self {
+        Self::from_path(&join_paths(paths))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::airtime;
+    use approx::assert_relative_eq;
+    use kexedit_core::Float3;
+
+    fn point(arc: f32, height: f32, velocity: f32, normal_force: f32, lateral_force: f32) -> Point {
+        let mut point = Point::DEFAULT.with_forces(normal_force, lateral_force);
+        point.heart_position = Float3::new(0.0, height, 0.0);
+        point.heart_arc = arc;
+        point.velocity = velocity;
+        point
+    }
+
+    #[test]
+    fn empty_path_returns_default() {
+        assert_eq!(RideStats::from_path(&[]), RideStats::default());
+    }
+
+    #[test]
+    fn from_path_locates_extremes() {
+        let path = [
+            point(0.0, 10.0, 5.0, 1.0, 0.0),
+            point(1.0, 30.0, 8.0, -0.5, 0.2),
+            point(2.0, 2.0, 20.0, 4.0, -0.3),
+            point(3.0, 5.0, 15.0, 1.0, 0.0),
+        ];
+
+        let stats = RideStats::from_path(&path);
+
+        assert_relative_eq!(stats.length, 3.0);
+        assert_relative_eq!(stats.duration, 3.0 * sim::DT);
+        assert_eq!(stats.max_height, Extreme { value: 30.0, arc: 1.0, time: sim::DT });
+        assert_relative_eq!(stats.min_height.value, 2.0);
+        assert_relative_eq!(stats.min_height.arc, 2.0);
+        assert_relative_eq!(stats.max_velocity.value, 20.0);
+        assert_relative_eq!(stats.max_normal_force.value, 4.0);
+        assert_relative_eq!(stats.min_normal_force.value, -0.5);
+        assert_relative_eq!(stats.max_lateral_force.value, 0.2);
+        assert_relative_eq!(stats.min_lateral_force.value, -0.3);
+        assert_relative_eq!(stats.airtime, sim::DT);
+    }
+
+    #[test]
+    fn from_paths_offsets_later_paths_and_skips_shared_anchor() {
+        let first = [point(0.0, 10.0, 5.0, 1.0, 0.0), point(2.0, 12.0, 6.0, -1.0, 0.0)];
+        let second = [
+            point(2.0, 12.0, 6.0, -1.0, 0.0),
+            point(5.0, 40.0, 7.0, 1.0, 0.0),
+            point(7.0, 8.0, 9.0, 1.0, 0.0),
+        ];
+
+        let stats = RideStats::from_paths(&[&first, &second]);
+
+        assert_relative_eq!(stats.length, 7.0);
+        assert_relative_eq!(stats.duration, 3.0 * sim::DT);
+        assert_relative_eq!(stats.max_height.arc, 5.0);
+        assert_relative_eq!(stats.max_height.time, 2.0 * sim::DT);
+        assert_relative_eq!(stats.airtime, sim::DT);
+    }
+
+    #[test]
+    fn airtime_counts_first_point_and_floater_threshold() {
+        let path = [point(0.0, 10.0, 5.0, -0.5, 0.0), point(1.0, 10.0, 5.0, 0.1, 0.0), point(2.0, 10.0, 5.0, 1.0, 0.0)];
+
+        let stats = RideStats::from_paths(&[&path]);
+
+        assert_relative_eq!(stats.airtime, 2.0 * sim::DT);
+        assert_relative_eq!(stats.airtime, airtime::analyze(&path, &AirtimeThresholds::DEFAULT).total);
+    }
+}
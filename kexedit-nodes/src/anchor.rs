@@ -1,6 +1,6 @@
 use kexedit_core::{Float3, Point, Quaternion};
 
-fn from_euler(pitch: f32, yaw: f32, roll: f32) -> (Float3, Float3, Float3) {
+pub(crate) fn from_euler(pitch: f32, yaw: f32, roll: f32) -> (Float3, Float3, Float3) {
     let pitch_quat = Quaternion::from_axis_angle(Float3::RIGHT, pitch);
     let yaw_quat = Quaternion::from_axis_angle(Float3::UP, yaw);
 
@@ -44,6 +44,7 @@ pub fn build(
         0.0,
         0.0,
         0.0,
+        0.0,
         heart_offset,
         friction,
         resistance,
@@ -0,0 +1,235 @@
+use kexedit_core::{sim, Keyframe, Point};
+
+use crate::domain::{Locator, PropertyCurve};
+use crate::geometric::step_geometric;
+use crate::{DurationType, IterationConfig, PropertyId};
+
+const MAX_ITERATIONS: usize = 1_000_000;
+
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BrakeModel {
+    /// Constant deceleration until the target velocity is reached.
+    Friction = 0,
+    /// Eddy-current deceleration proportional to velocity, peaking at the entry velocity.
+    Magnetic = 1,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BrakeParams {
+    pub model: BrakeModel,
+    pub target_velocity: f32,
+    /// Deceleration limit in G.
+    pub max_deceleration: f32,
+}
+
+impl BrakeParams {
+    pub const fn new(model: BrakeModel, target_velocity: f32, max_deceleration: f32) -> Self {
+        Self {
+            model,
+            target_velocity,
+            max_deceleration,
+        }
+    }
+
+    fn deceleration(&self, velocity: f32, entry_velocity: f32) -> f32 {
+        let max = self.max_deceleration.max(0.0) * sim::G;
+        match self.model {
+            BrakeModel::Friction => max,
+            BrakeModel::Magnetic => {
+                if entry_velocity < sim::EPSILON {
+                    max
+                } else {
+                    max * (velocity / entry_velocity).min(1.0)
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BrakeOutput {
+    pub points: Vec<Point>,
+    /// Energy removed by the brake, per unit mass.
+    pub dissipated_energy: f32,
+}
+
+pub struct BrakeNode;
+
+impl BrakeNode {
+    /// Builds a straight brake run along the anchor direction.
+    ///
+    /// Each step advances like an unpowered geometric section, then the brake
+    /// removes kinetic energy until `target_velocity` is reached, never exceeding
+    /// the model's deceleration.
+    #[allow(clippy::too_many_arguments)]
+    pub fn build(
+        anchor: &Point,
+        config: &IterationConfig,
+        brake: &BrakeParams,
+        heart_offset: &[Keyframe],
+        friction: &[Keyframe],
+        resistance: &[Keyframe],
+        anchor_heart: f32,
+        anchor_friction: f32,
+        anchor_resistance: f32,
+    ) -> BrakeOutput {
+        let mut heart_offset = PropertyCurve::new(heart_offset, config.domains.get(PropertyId::HeartOffset));
+        let mut friction = PropertyCurve::new(friction, config.domains.get(PropertyId::Friction));
+        let mut resistance = PropertyCurve::new(resistance, config.domains.get(PropertyId::Resistance));
+        let mut locator = Locator::new(anchor);
+
+        let mut points = Vec::new();
+        points.push(*anchor);
+
+        let mut state = *anchor;
+        let mut accumulated_roll = 0.0;
+        let mut dissipated_energy = 0.0;
+        let target_velocity = brake.target_velocity.max(0.0);
+
+        let point_count = (sim::HZ * config.duration).floor() as usize;
+        let end_length = anchor.heart_arc + config.duration;
+        let mut index = 1;
+
+        loop {
+            let finished = match config.duration_type {
+                DurationType::Time => index >= point_count,
+                DurationType::Distance => state.heart_arc >= end_length || index > MAX_ITERATIONS,
+            };
+            if finished {
+                break;
+            }
+
+            let mut prev = state;
+            if prev.velocity < sim::MIN_VELOCITY {
+                if prev.frame().pitch() < 0.0 {
+                    prev = prev.with_velocity(sim::MIN_VELOCITY, prev.heart_offset, prev.friction, true);
+                } else {
+                    break;
+                }
+            }
+
+            let param = match config.duration_type {
+                DurationType::Time => index as f32 / sim::HZ,
+                DurationType::Distance => prev.heart_arc - anchor.heart_arc + prev.velocity / sim::HZ,
+            };
+            let here = locator.locate(&prev, index, param);
+
+            let heart_offset_val = heart_offset.evaluate(&here, anchor_heart);
+            let friction_val = friction.evaluate(&here, anchor_friction);
+            let resistance_val = resistance.evaluate(&here, anchor_resistance);
+
+            let curr = step_geometric(
+                &prev,
+                heart_offset_val,
+                friction_val,
+                resistance_val,
+                0.0,
+                0.0,
+                0.0,
+                prev.velocity / sim::HZ,
+                false,
+                false,
+                0.0,
+                &mut accumulated_roll,
+                state.velocity,
+            );
+
+            let free_velocity = curr.velocity;
+            let curr = if free_velocity > target_velocity {
+                let deceleration = brake.deceleration(prev.velocity, anchor.velocity);
+                let braked_velocity = (free_velocity - deceleration * sim::DT).max(target_velocity);
+                let removed = 0.5 * (free_velocity * free_velocity - braked_velocity * braked_velocity);
+                dissipated_energy += removed;
+                curr.with_velocity_and_energy(braked_velocity, curr.energy - removed, curr.friction_origin)
+                    .with_longitudinal_force(sim::longitudinal_force(state.velocity, braked_velocity))
+            } else {
+                curr
+            };
+
+            points.push(curr);
+            locator.advance(&state, &curr);
+            state = curr;
+            index += 1;
+        }
+
+        BrakeOutput {
+            points,
+            dissipated_energy,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::anchor_at;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn trim_brake_reaches_target_velocity() {
+        let anchor = anchor_at(3.0, 20.0);
+        let config = IterationConfig::new(40.0, DurationType::Distance);
+        let brake = BrakeParams::new(BrakeModel::Friction, 12.0, 0.5);
+
+        let output = BrakeNode::build(&anchor, &config, &brake, &[], &[], &[], 1.1, 0.0, 0.0);
+
+        let exit = output.points.last().unwrap();
+        assert_relative_eq!(exit.velocity, 12.0, epsilon = 1e-2);
+    }
+
+    #[test]
+    fn deceleration_respects_limit() {
+        let anchor = anchor_at(3.0, 20.0);
+        let config = IterationConfig::new(2.0, DurationType::Time);
+        let brake = BrakeParams::new(BrakeModel::Friction, 0.0, 0.8);
+
+        let output = BrakeNode::build(&anchor, &config, &brake, &[], &[], &[], 1.1, 0.0, 0.0);
+
+        for point in &output.points {
+            assert!(
+                point.longitudinal_force >= -0.8 - 1e-2,
+                "deceleration {} exceeds limit",
+                point.longitudinal_force
+            );
+        }
+        assert!(output.points[10].longitudinal_force < -0.7);
+    }
+
+    #[test]
+    fn dissipated_energy_matches_energy_loss() {
+        let anchor = anchor_at(3.0, 20.0);
+        let config = IterationConfig::new(50.0, DurationType::Distance);
+        let brake = BrakeParams::new(BrakeModel::Magnetic, 8.0, 1.0);
+
+        let output = BrakeNode::build(&anchor, &config, &brake, &[], &[], &[], 1.1, 0.0, 0.0);
+
+        let exit = output.points.last().unwrap();
+        assert_relative_eq!(output.dissipated_energy, anchor.energy - exit.energy, epsilon = 1e-2);
+    }
+
+    #[test]
+    fn magnetic_brake_fades_with_velocity() {
+        let anchor = anchor_at(3.0, 20.0);
+        let config = IterationConfig::new(3.0, DurationType::Time);
+        let brake = BrakeParams::new(BrakeModel::Magnetic, 0.0, 1.0);
+
+        let output = BrakeNode::build(&anchor, &config, &brake, &[], &[], &[], 1.1, 0.0, 0.0);
+
+        let early = output.points[2].longitudinal_force;
+        let late = output.points[output.points.len() - 1].longitudinal_force;
+        assert!(early < late, "magnetic brake should weaken as the train slows");
+    }
+
+    #[test]
+    fn slower_train_is_not_accelerated() {
+        let anchor = anchor_at(3.0, 5.0);
+        let config = IterationConfig::new(10.0, DurationType::Distance);
+        let brake = BrakeParams::new(BrakeModel::Friction, 10.0, 1.0);
+
+        let output = BrakeNode::build(&anchor, &config, &brake, &[], &[], &[], 1.1, 0.0, 0.0);
+
+        assert_relative_eq!(output.dissipated_energy, 0.0, epsilon = 1e-6);
+        assert!(output.points.last().unwrap().velocity <= 5.0 + 1e-3);
+    }
+}
@@ -1,7 +1,10 @@
 use kexedit_core::{
-    evaluate, sim, Frame, Float3, Keyframe, Point, Quaternion,
+    sim, Frame, Float3, Keyframe, Point, Quaternion,
 };
 
+use crate::domain::{Locator, PropertyCurve, PropertyDomains};
+use crate::PropertyId;
+
 const MAX_ITERATIONS: usize = 1_000_000;
 
 pub struct BridgeNode;
@@ -18,10 +21,17 @@ impl BridgeNode {
         heart_offset: &[Keyframe],
         friction: &[Keyframe],
         resistance: &[Keyframe],
+        domains: &PropertyDomains,
         anchor_heart: f32,
         anchor_friction: f32,
         anchor_resistance: f32,
     ) -> Vec<Point> {
+        let mut driven_velocity = PropertyCurve::new(driven_velocity, domains.get(PropertyId::DrivenVelocity));
+        let mut heart_offset = PropertyCurve::new(heart_offset, domains.get(PropertyId::HeartOffset));
+        let mut friction = PropertyCurve::new(friction, domains.get(PropertyId::Friction));
+        let mut resistance = PropertyCurve::new(resistance, domains.get(PropertyId::Resistance));
+        let mut locator = Locator::new(anchor);
+
         let mut result = Vec::new();
         result.push(*anchor);
 
@@ -58,10 +68,11 @@ impl BridgeNode {
 
             let mut prev = state;
             let t = (result.len() - 1) as f32 / sim::HZ;
+            let here = locator.locate(&prev, result.len(), t);
 
             let mut advance_velocity = prev.velocity;
             if driven {
-                let velocity = evaluate(driven_velocity, t, prev.velocity);
+                let velocity = driven_velocity.evaluate(&here, prev.velocity);
                 if velocity < sim::MIN_VELOCITY {
                     break;
                 }
@@ -75,9 +86,9 @@ impl BridgeNode {
                 }
             }
 
-            let heart_offset_val = evaluate(heart_offset, t, anchor_heart);
-            let friction_val = evaluate(friction, t, anchor_friction);
-            let resistance_val = evaluate(resistance, t, anchor_resistance);
+            let heart_offset_val = heart_offset.evaluate(&here, anchor_heart);
+            let friction_val = friction.evaluate(&here, anchor_friction);
+            let resistance_val = resistance.evaluate(&here, anchor_resistance);
 
             let expected_advancement = advance_velocity / sim::HZ;
             let desired_distance = path_distance + expected_advancement;
@@ -122,7 +133,7 @@ impl BridgeNode {
 
             let (new_energy, new_velocity);
             if driven {
-                new_velocity = evaluate(driven_velocity, t, prev.velocity);
+                new_velocity = driven_velocity.evaluate(&here, prev.velocity);
                 let prev_center_y = prev.frame().spine_position(prev.heart_position, prev_heart_offset * 0.9).y;
                 new_energy = 0.5 * new_velocity * new_velocity + sim::G * prev_center_y;
             } else {
@@ -149,6 +160,7 @@ impl BridgeNode {
                 new_energy,
                 normal_force,
                 lateral_force,
+                sim::longitudinal_force(state.velocity, new_velocity),
                 heart_arc,
                 spine_arc,
                 spine_advance,
@@ -160,6 +172,7 @@ impl BridgeNode {
             );
 
             result.push(state);
+            locator.advance(&prev, &state);
             prev_heart_offset = heart_offset_val;
             prev_friction = friction_val;
         }
@@ -341,6 +354,7 @@ mod tests {
             0.0,
             0.0,
             0.0,
+            0.0,
         );
 
         let target = Point::new(
@@ -360,6 +374,7 @@ mod tests {
             0.0,
             0.0,
             0.0,
+            0.0,
         );
 
         let result = BridgeNode::build(
@@ -372,6 +387,7 @@ mod tests {
             &[Keyframe::simple(0.0, 0.0)],
             &[Keyframe::simple(0.0, 0.0)],
             &[Keyframe::simple(0.0, 0.0)],
+            &PropertyDomains::DEFAULT,
             0.0,
             0.0,
             0.0,
@@ -400,6 +416,7 @@ mod tests {
             0.0,
             0.0,
             0.0,
+            0.0,
         );
 
         let result = BridgeNode::build(
@@ -412,6 +429,7 @@ mod tests {
             &[Keyframe::simple(0.0, 0.0)],
             &[Keyframe::simple(0.0, 0.0)],
             &[Keyframe::simple(0.0, 0.0)],
+            &PropertyDomains::DEFAULT,
             0.0,
             0.0,
             0.0,
@@ -454,6 +472,7 @@ mod tests {
                 &heart_offset,
                 &friction,
                 &resistance,
+                &PropertyDomains::DEFAULT,
                 section.inputs.anchor.heart,
                 section.inputs.anchor.friction,
                 section.inputs.anchor.resistance,
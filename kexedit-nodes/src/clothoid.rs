@@ -0,0 +1,326 @@
+use kexedit_core::{sim, Curvature, Forces, Keyframe, Point};
+
+use crate::domain::{Locator, PropertyCurve, PropertyDomains};
+use crate::roll::{roll_to_angle, RollChannel};
+use crate::PropertyId;
+
+const MAX_ITERATIONS: usize = 1_000_000;
+
+/// Curvature in 1/m, split into the pitch and yaw components of the turn.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ClothoidCurvature {
+    /// Positive pitches up, like a curved section with a 90° axis.
+    pub pitch: f32,
+    /// Positive turns like a curved section with a 0° axis.
+    pub yaw: f32,
+}
+
+impl ClothoidCurvature {
+    pub const ZERO: Self = Self::new(0.0, 0.0);
+
+    pub const fn new(pitch: f32, yaw: f32) -> Self {
+        Self { pitch, yaw }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ClothoidParams {
+    /// Heartline length of the transition in metres.
+    pub length: f32,
+    pub start: ClothoidCurvature,
+    pub end: ClothoidCurvature,
+}
+
+impl ClothoidParams {
+    pub const fn new(length: f32, start: ClothoidCurvature, end: ClothoidCurvature) -> Self {
+        Self { length, start, end }
+    }
+
+    /// Curvature `distance` metres into the transition, linear from start to end.
+    pub fn curvature_at(&self, distance: f32) -> ClothoidCurvature {
+        let s = if self.length > sim::EPSILON {
+            (distance / self.length).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        ClothoidCurvature::new(
+            self.start.pitch + (self.end.pitch - self.start.pitch) * s,
+            self.start.yaw + (self.end.yaw - self.start.yaw) * s,
+        )
+    }
+
+    /// Turn angle and `step_clothoid` axis, both in degrees, for a step of
+    /// `step_length` starting `distance` into the transition. Curvature is taken
+    /// at the step's midpoint, which integrates the linear ramp exactly.
+    fn turn(&self, distance: f32, step_length: f32) -> (f32, f32) {
+        let curvature = self.curvature_at(distance + 0.5 * step_length);
+        let magnitude = curvature.pitch.hypot(curvature.yaw);
+        let angle = (magnitude * step_length).to_degrees();
+        let axis = curvature.pitch.atan2(curvature.yaw).to_degrees();
+        (angle, axis)
+    }
+}
+
+/// Euler-spiral transition: curvature changes linearly with distance between
+/// two curvatures, so normal and lateral G ramp without the jumps of a
+/// radius change. The last step is shortened so the transition ends at
+/// exactly `length`. Curvature is applied in the track's own frame, so pitch
+/// transitions can run through vertical, as at a loop entry.
+pub struct ClothoidNode;
+
+impl ClothoidNode {
+    /// Property curves are keyed by distance along the transition by default.
+    /// Roll speed is in radians per second, as in curved sections.
+    #[allow(clippy::too_many_arguments)]
+    pub fn build(
+        anchor: &Point,
+        params: &ClothoidParams,
+        driven: bool,
+        roll_channel: RollChannel,
+        roll: &[Keyframe],
+        driven_velocity: &[Keyframe],
+        heart_offset: &[Keyframe],
+        friction: &[Keyframe],
+        resistance: &[Keyframe],
+        domains: &PropertyDomains,
+        anchor_heart: f32,
+        anchor_friction: f32,
+        anchor_resistance: f32,
+    ) -> Vec<Point> {
+        let mut driven_velocity = PropertyCurve::new(driven_velocity, domains.get(PropertyId::DrivenVelocity));
+        let mut heart_offset = PropertyCurve::new(heart_offset, domains.get(PropertyId::HeartOffset));
+        let mut friction = PropertyCurve::new(friction, domains.get(PropertyId::Friction));
+        let mut resistance = PropertyCurve::new(resistance, domains.get(PropertyId::Resistance));
+        let mut roll = PropertyCurve::new(roll, domains.get(roll_channel.property()));
+        let mut locator = Locator::new(anchor);
+
+        let mut result = Vec::new();
+        result.push(*anchor);
+
+        let mut state = *anchor;
+        let mut distance = 0.0;
+        let mut prev_heart_offset = anchor_heart;
+        let mut prev_friction = anchor_friction;
+
+        while distance < params.length {
+            if result.len() > MAX_ITERATIONS {
+                break;
+            }
+
+            let mut prev = state;
+            let here = locator.locate(&prev, result.len(), distance + prev.velocity / sim::HZ);
+
+            if driven {
+                let velocity = driven_velocity.evaluate(&here, prev.velocity);
+                if velocity < sim::MIN_VELOCITY {
+                    break;
+                }
+                prev = prev.with_velocity(velocity, prev_heart_offset, prev_friction, true);
+            } else if prev.velocity < sim::MIN_VELOCITY {
+                if prev.frame().pitch() < 0.0 {
+                    prev = prev.with_velocity(sim::MIN_VELOCITY, prev_heart_offset, prev_friction, true);
+                } else {
+                    break;
+                }
+            }
+
+            let heart_offset_val = heart_offset.evaluate(&here, anchor_heart);
+            let friction_val = friction.evaluate(&here, anchor_friction);
+            let resistance_val = resistance.evaluate(&here, anchor_resistance);
+
+            let step_length = (prev.velocity / sim::HZ).min(params.length - distance);
+            let (delta_angle, axis) = params.turn(distance, step_length);
+
+            let roll_speed_val = match roll_channel {
+                RollChannel::Speed => roll.evaluate(&here, 0.0),
+                RollChannel::Angle => {
+                    let unrolled = step_clothoid(
+                        &prev,
+                        axis,
+                        delta_angle,
+                        step_length,
+                        0.0,
+                        heart_offset_val,
+                        friction_val,
+                        resistance_val,
+                        driven,
+                        prev.velocity,
+                    );
+                    roll_to_angle(&unrolled, roll.evaluate(&here, unrolled.roll())) * sim::HZ
+                }
+            };
+
+            let curr = step_clothoid(
+                &prev,
+                axis,
+                delta_angle,
+                step_length,
+                roll_speed_val,
+                heart_offset_val,
+                friction_val,
+                resistance_val,
+                driven,
+                state.velocity,
+            );
+
+            result.push(curr);
+            locator.advance(&state, &curr);
+            state = curr;
+            distance += step_length;
+            prev_heart_offset = heart_offset_val;
+            prev_friction = friction_val;
+        }
+
+        result
+    }
+}
+
+/// Turns `prev` by `delta_angle` degrees about the axis `axis` degrees from
+/// its normal towards its lateral, as `curved::step_curved` does, and advances
+/// `step_length` metres. The whole frame turns about that axis instead of being
+/// re-levelled against world up, so the step holds through vertical.
+///
+/// `entry_velocity` is the velocity the previous point was built with, before
+/// any driven or stall override of `prev`; the longitudinal force is measured
+/// from it so those changes are included.
+#[allow(clippy::too_many_arguments)]
+fn step_clothoid(
+    prev: &Point,
+    axis: f32,
+    delta_angle: f32,
+    step_length: f32,
+    roll_speed_val: f32,
+    heart_offset_val: f32,
+    friction_val: f32,
+    resistance_val: f32,
+    driven: bool,
+    entry_velocity: f32,
+) -> Point {
+    let prev_frame = prev.frame();
+    let axis_rad = axis.to_radians();
+    let curve_axis = prev.normal * -axis_rad.cos() + prev.lateral * axis_rad.sin();
+    let turned = prev_frame.rotate_around(curve_axis, delta_angle.to_radians());
+
+    let half_step_distance = 0.5 * step_length;
+    let prev_spine_pos = prev.spine_position(heart_offset_val);
+    let curr_spine_pos_if_heart_static = prev.heart_position + turned.normal * heart_offset_val;
+    let curr_heart_position = prev.heart_position
+        + turned.direction * half_step_distance
+        + prev.direction * half_step_distance
+        + (prev_spine_pos - curr_spine_pos_if_heart_static);
+
+    let curr_frame = turned.with_roll(roll_speed_val / sim::HZ);
+
+    let spine_advance = (curr_frame.spine_position(curr_heart_position, heart_offset_val) - prev_spine_pos).magnitude();
+    let heart_advance = (curr_heart_position - prev.heart_position).magnitude();
+
+    let (energy, velocity) = if driven {
+        (prev.energy, prev.velocity)
+    } else {
+        let center_y = curr_frame.spine_position(curr_heart_position, 0.9 * heart_offset_val).y;
+        sim::update_energy(
+            prev.energy,
+            prev.velocity,
+            center_y,
+            prev.heart_arc + spine_advance - prev.friction_origin,
+            friction_val,
+            resistance_val,
+        )
+    };
+
+    let curvature = Curvature::from_frames(curr_frame, prev_frame);
+    let forces = Forces::compute(curvature, curr_frame, velocity, heart_advance);
+
+    Point::new(
+        curr_heart_position,
+        curr_frame.direction,
+        curr_frame.normal,
+        curr_frame.lateral,
+        velocity,
+        energy,
+        forces.normal,
+        forces.lateral,
+        sim::longitudinal_force(entry_velocity, velocity),
+        prev.heart_arc + spine_advance,
+        prev.spine_arc + heart_advance,
+        heart_advance,
+        prev.friction_origin,
+        roll_speed_val,
+        heart_offset_val,
+        friction_val,
+        resistance_val,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::anchor;
+    use approx::assert_relative_eq;
+
+    fn build(params: &ClothoidParams) -> Vec<Point> {
+        ClothoidNode::build(
+            &anchor(), params, true, RollChannel::Speed, &[], &[Keyframe::simple(0.0, 15.0)], &[], &[], &[],
+            &PropertyDomains::DEFAULT, 1.1, 0.0, 0.0,
+        )
+    }
+
+    fn turned_degrees(from: &Point, to: &Point) -> f32 {
+        from.direction.dot(to.direction).clamp(-1.0, 1.0).acos().to_degrees()
+    }
+
+    #[test]
+    fn curvature_ramps_linearly() {
+        let params = ClothoidParams::new(30.0, ClothoidCurvature::ZERO, ClothoidCurvature::new(0.0, 0.05));
+
+        assert_relative_eq!(params.curvature_at(15.0).yaw, 0.025);
+        assert_relative_eq!(params.curvature_at(45.0).yaw, 0.05);
+    }
+
+    #[test]
+    fn yaw_transition_turns_by_mean_curvature_times_length() {
+        let params = ClothoidParams::new(30.0, ClothoidCurvature::ZERO, ClothoidCurvature::new(0.0, 0.05));
+
+        let result = build(&params);
+        let last = result.last().unwrap();
+
+        assert_relative_eq!(last.heart_arc - result[0].heart_arc, 30.0, epsilon = 1e-3);
+        assert_relative_eq!(turned_degrees(&result[0], last), 0.75f32.to_degrees(), epsilon = 0.5);
+        assert!(last.direction.y.abs() < 1e-3);
+    }
+
+    #[test]
+    fn turn_rate_grows_linearly_with_distance() {
+        let params = ClothoidParams::new(30.0, ClothoidCurvature::ZERO, ClothoidCurvature::new(0.0, 0.05));
+
+        let result = build(&params);
+        let quarter = result.len() / 4;
+        let half = result.len() / 2;
+        let rate = |i: usize| result[i - 1].direction.cross(result[i].direction).magnitude();
+
+        assert_relative_eq!(rate(half) / rate(quarter), (half as f32 - 0.5) / (quarter as f32 - 0.5), epsilon = 1e-2);
+    }
+
+    #[test]
+    fn pitch_transition_runs_through_vertical() {
+        let params = ClothoidParams::new(40.0, ClothoidCurvature::ZERO, ClothoidCurvature::new(0.1, 0.0));
+
+        let result = build(&params);
+        let first = &result[0];
+        let last = result.last().unwrap();
+
+        assert_relative_eq!(turned_degrees(first, last), 2f32.to_degrees(), epsilon = 0.5);
+        assert!(last.direction.y > 0.9 && last.direction.z > 0.4);
+        assert!(last.direction.x.abs() < 1e-4);
+        assert!(last.lateral.dot(first.lateral) > 1.0 - 1e-4);
+    }
+
+    #[test]
+    fn positive_pitch_curvature_pitches_up() {
+        let params = ClothoidParams::new(20.0, ClothoidCurvature::ZERO, ClothoidCurvature::new(0.05, 0.0));
+
+        let result = build(&params);
+
+        assert!(result.last().unwrap().direction.y > 0.1);
+    }
+}
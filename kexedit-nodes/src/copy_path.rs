@@ -1,7 +1,10 @@
 use kexedit_core::{
-    evaluate, sim, Frame, Float3, Keyframe, Point,
+    sim, Frame, Float3, Keyframe, Point,
 };
 
+use crate::domain::{Locator, PropertyCurve, PropertyDomains};
+use crate::PropertyId;
+
 const MAX_ITERATIONS: usize = 1_000_000;
 
 pub struct CopyPathNode;
@@ -18,10 +21,17 @@ impl CopyPathNode {
         heart_offset: &[Keyframe],
         friction: &[Keyframe],
         resistance: &[Keyframe],
+        domains: &PropertyDomains,
         anchor_heart: f32,
         anchor_friction: f32,
         anchor_resistance: f32,
     ) -> Vec<Point> {
+        let mut driven_velocity = PropertyCurve::new(driven_velocity, domains.get(PropertyId::DrivenVelocity));
+        let mut heart_offset = PropertyCurve::new(heart_offset, domains.get(PropertyId::HeartOffset));
+        let mut friction = PropertyCurve::new(friction, domains.get(PropertyId::Friction));
+        let mut resistance = PropertyCurve::new(resistance, domains.get(PropertyId::Resistance));
+        let mut locator = Locator::new(anchor);
+
         let mut result = Vec::new();
         result.push(*anchor);
 
@@ -72,6 +82,7 @@ impl CopyPathNode {
                 energy,
                 anchor.normal_force,
                 anchor.lateral_force,
+                anchor.longitudinal_force,
                 anchor.heart_arc,
                 anchor.spine_arc,
                 anchor.heart_advance,
@@ -91,10 +102,11 @@ impl CopyPathNode {
 
             let mut prev = state;
             let t = index as f32 / sim::HZ;
+            let here = locator.locate(&prev, result.len(), t);
 
             let mut advance_velocity = prev.velocity;
             if driven {
-                let velocity = evaluate(driven_velocity, t, prev.velocity);
+                let velocity = driven_velocity.evaluate(&here, prev.velocity);
                 if velocity < sim::MIN_VELOCITY {
                     break;
                 }
@@ -109,9 +121,9 @@ impl CopyPathNode {
                 }
             }
 
-            let heart_offset_val = evaluate(heart_offset, t, anchor_heart);
-            let friction_val = evaluate(friction, t, anchor_friction);
-            let resistance_val = evaluate(resistance, t, anchor_resistance);
+            let heart_offset_val = heart_offset.evaluate(&here, anchor_heart);
+            let friction_val = friction.evaluate(&here, anchor_friction);
+            let resistance_val = resistance.evaluate(&here, anchor_resistance);
 
             let expected_advancement = advance_velocity / sim::HZ;
             let desired_distance = distance + expected_advancement;
@@ -150,7 +162,7 @@ impl CopyPathNode {
 
             let (new_energy, new_velocity);
             if driven {
-                new_velocity = evaluate(driven_velocity, t, prev.velocity);
+                new_velocity = driven_velocity.evaluate(&here, prev.velocity);
                 let prev_center_y = prev.frame().spine_position(prev.heart_position, prev_heart_offset * 0.9).y;
                 new_energy = 0.5 * new_velocity * new_velocity + sim::G * prev_center_y;
             } else {
@@ -177,6 +189,7 @@ impl CopyPathNode {
                 new_energy,
                 normal_force,
                 lateral_force,
+                sim::longitudinal_force(state.velocity, new_velocity),
                 heart_arc,
                 spine_arc,
                 spine_advance,
@@ -188,6 +201,7 @@ impl CopyPathNode {
             );
 
             result.push(state);
+            locator.advance(&prev, &state);
             prev_heart_offset = heart_offset_val;
             prev_friction = friction_val;
         }
@@ -334,6 +348,7 @@ mod tests {
             0.0,
             0.0,
             0.0,
+            0.0,
         );
 
         let source_path = vec![
@@ -354,6 +369,7 @@ mod tests {
                 0.0,
                 0.0,
                 0.0,
+                0.0,
             ),
             Point::new(
                 Float3::new(1.0, 0.0, 0.0),
@@ -364,6 +380,7 @@ mod tests {
                 50.0,
                 1.0,
                 0.0,
+                0.0,
                 1.0,
                 1.0,
                 1.0,
@@ -385,6 +402,7 @@ mod tests {
             &[Keyframe::simple(0.0, 0.0)],
             &[Keyframe::simple(0.0, 0.0)],
             &[Keyframe::simple(0.0, 0.0)],
+            &PropertyDomains::DEFAULT,
             0.0,
             0.0,
             0.0,
@@ -413,6 +431,7 @@ mod tests {
             0.0,
             0.0,
             0.0,
+            0.0,
         );
 
         let result = CopyPathNode::build(
@@ -425,6 +444,7 @@ mod tests {
             &[Keyframe::simple(0.0, 0.0)],
             &[Keyframe::simple(0.0, 0.0)],
             &[Keyframe::simple(0.0, 0.0)],
+            &PropertyDomains::DEFAULT,
             0.0,
             0.0,
             0.0,
@@ -467,6 +487,7 @@ mod tests {
                 &heart_offset,
                 &friction,
                 &resistance,
+                &PropertyDomains::DEFAULT,
                 section.inputs.anchor.heart,
                 section.inputs.anchor.friction,
                 section.inputs.anchor.resistance,
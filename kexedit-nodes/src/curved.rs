@@ -1,7 +1,11 @@
 use kexedit_core::{
-    evaluate, sim, Curvature, Forces, Frame, Float3, Keyframe, Point, Quaternion,
+    sim, Curvature, Forces, Frame, Float3, Keyframe, Point, Quaternion,
 };
 
+use crate::domain::{CurveDomain, Locator, PropertyCurve, PropertyDomains};
+use crate::roll::{roll_to_angle, solve_bank, RollChannel};
+use crate::PropertyId;
+
 const MAX_ITERATIONS: usize = 1_000_000;
 
 pub struct CurvedNode;
@@ -16,15 +20,27 @@ impl CurvedNode {
         lead_in: f32,
         lead_out: f32,
         driven: bool,
-        roll_speed: &[Keyframe],
+        auto_bank: bool,
+        roll_channel: RollChannel,
+        roll: &[Keyframe],
+        lateral_force: &[Keyframe],
         driven_velocity: &[Keyframe],
         heart_offset: &[Keyframe],
         friction: &[Keyframe],
         resistance: &[Keyframe],
+        domains: &PropertyDomains,
         anchor_heart: f32,
         anchor_friction: f32,
         anchor_resistance: f32,
     ) -> Vec<Point> {
+        let mut driven_velocity = PropertyCurve::new(driven_velocity, domains.get(PropertyId::DrivenVelocity));
+        let mut heart_offset = PropertyCurve::new(heart_offset, domains.get(PropertyId::HeartOffset));
+        let mut friction = PropertyCurve::new(friction, domains.get(PropertyId::Friction));
+        let mut resistance = PropertyCurve::new(resistance, domains.get(PropertyId::Resistance));
+        let mut lateral_force = PropertyCurve::new(lateral_force, domains.get(PropertyId::LateralForce).resolve(CurveDomain::Angle));
+        let mut roll = PropertyCurve::new(roll, domains.get(roll_channel.property()).resolve(CurveDomain::Angle));
+        let locator = Locator::new(anchor);
+
         let mut result = Vec::new();
         result.push(*anchor);
 
@@ -49,9 +65,11 @@ impl CurvedNode {
 
             let mut prev = state;
             let t = index as f32 / sim::HZ;
+            let mut here = locator.locate(&prev, index, t);
+            here.angle = angle;
 
             if driven {
-                let velocity = evaluate(driven_velocity, t, prev.velocity);
+                let velocity = driven_velocity.evaluate(&here, prev.velocity);
                 if velocity < sim::MIN_VELOCITY {
                     break;
                 }
@@ -64,9 +82,9 @@ impl CurvedNode {
                 }
             }
 
-            let heart_offset_val = evaluate(heart_offset, t, anchor_heart);
-            let friction_val = evaluate(friction, t, anchor_friction);
-            let resistance_val = evaluate(resistance, t, anchor_resistance);
+            let heart_offset_val = heart_offset.evaluate(&here, anchor_heart);
+            let friction_val = friction.evaluate(&here, anchor_friction);
+            let resistance_val = resistance.evaluate(&here, anchor_resistance);
 
             let mut delta_angle = prev.velocity / radius / sim::HZ * 1.0f32.to_degrees();
 
@@ -99,7 +117,39 @@ impl CurvedNode {
             }
 
             angle += delta_angle;
-            let roll_speed_val = evaluate(roll_speed, angle, 0.0);
+            here.angle = angle;
+            let roll_speed_val = if auto_bank {
+                let target = lateral_force.evaluate(&here, 0.0);
+                let (delta_roll, _) = solve_bank(target, |delta_roll| {
+                    step_curved(
+                        &prev,
+                        axis,
+                        delta_angle,
+                        delta_roll * sim::HZ,
+                        heart_offset_val,
+                        friction_val,
+                        resistance_val,
+                        driven,
+                        prev.velocity,
+                    )
+                });
+                delta_roll * sim::HZ
+            } else if roll_channel == RollChannel::Angle {
+                let unrolled = step_curved(
+                    &prev,
+                    axis,
+                    delta_angle,
+                    0.0,
+                    heart_offset_val,
+                    friction_val,
+                    resistance_val,
+                    driven,
+                    prev.velocity,
+                );
+                roll_to_angle(&unrolled, roll.evaluate(&here, unrolled.roll())) * sim::HZ
+            } else {
+                roll.evaluate(&here, 0.0)
+            };
 
             let curr = step_curved(
                 &prev,
@@ -110,6 +160,7 @@ impl CurvedNode {
                 friction_val,
                 resistance_val,
                 driven,
+                state.velocity,
             );
 
             result.push(curr);
@@ -123,8 +174,11 @@ impl CurvedNode {
     }
 }
 
+/// `entry_velocity` is the velocity the previous point was built with, before
+/// any driven or stall override of `prev`; the longitudinal force is measured
+/// from it so those changes are included.
 #[allow(clippy::too_many_arguments)]
-fn step_curved(
+pub(crate) fn step_curved(
     prev: &Point,
     axis: f32,
     delta_angle: f32,
@@ -133,6 +187,7 @@ fn step_curved(
     friction_val: f32,
     resistance_val: f32,
     driven: bool,
+    entry_velocity: f32,
 ) -> Point {
     let prev_frame = prev.frame();
 
@@ -207,6 +262,7 @@ fn step_curved(
         new_energy,
         forces.normal,
         forces.lateral,
+        sim::longitudinal_force(entry_velocity, new_velocity),
         new_heart_arc,
         new_spine_arc,
         heart_advance,
@@ -221,6 +277,7 @@ fn step_curved(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixtures::anchor;
 
     #[test]
     fn curved_node_basic_arc() {
@@ -241,6 +298,7 @@ mod tests {
             0.0,
             0.0,
             0.0,
+            0.0,
         );
 
         let result = CurvedNode::build(
@@ -251,11 +309,15 @@ mod tests {
             0.0,
             0.0,
             true,
+            false,
+            RollChannel::Speed,
+            &[],
             &[],
             &[Keyframe::simple(0.0, 10.0)],
             &[Keyframe::simple(0.0, 0.0)],
             &[Keyframe::simple(0.0, 0.0)],
             &[Keyframe::simple(0.0, 0.0)],
+            &PropertyDomains::DEFAULT,
             0.0,
             0.0,
             0.0,
@@ -284,6 +346,7 @@ mod tests {
             0.0,
             0.0,
             0.0,
+            0.0,
         );
 
         let result = CurvedNode::build(
@@ -294,11 +357,15 @@ mod tests {
             10.0,
             0.0,
             true,
+            false,
+            RollChannel::Speed,
+            &[],
             &[],
             &[Keyframe::simple(0.0, 10.0)],
             &[Keyframe::simple(0.0, 0.0)],
             &[Keyframe::simple(0.0, 0.0)],
             &[Keyframe::simple(0.0, 0.0)],
+            &PropertyDomains::DEFAULT,
             0.0,
             0.0,
             0.0,
@@ -326,6 +393,7 @@ mod tests {
             0.0,
             0.0,
             0.0,
+            0.0,
         );
 
         let result = CurvedNode::build(
@@ -336,11 +404,15 @@ mod tests {
             0.0,
             10.0,
             true,
+            false,
+            RollChannel::Speed,
+            &[],
             &[],
             &[Keyframe::simple(0.0, 10.0)],
             &[Keyframe::simple(0.0, 0.0)],
             &[Keyframe::simple(0.0, 0.0)],
             &[Keyframe::simple(0.0, 0.0)],
+            &PropertyDomains::DEFAULT,
             0.0,
             0.0,
             0.0,
@@ -349,6 +421,58 @@ mod tests {
         assert!(result.len() > 1);
     }
 
+    #[test]
+    fn curved_node_auto_bank_zeroes_lateral_force() {
+        let anchor = anchor();
+
+        let result = CurvedNode::build(
+            &anchor, 20.0, 90.0, 0.0, 0.0, 0.0, false, true, RollChannel::Speed, &[], &[], &[], &[], &[], &[],
+            &PropertyDomains::DEFAULT, 1.1, 0.0, 0.0,
+        );
+
+        assert!(result.len() > 10);
+        for point in &result[1..] {
+            assert!(point.lateral_force.abs() < 1e-3, "lateral force {}", point.lateral_force);
+        }
+
+        let keys = crate::roll::roll_speed_keyframes(&result, CurveDomain::Angle, 1e-3);
+        assert!(!keys.is_empty());
+        assert!(keys.last().unwrap().time > 80.0);
+    }
+
+    #[test]
+    fn curved_node_roll_angle_keyframes_set_bank() {
+        let anchor = anchor();
+        let roll = [Keyframe::simple(0.0, 0.0), Keyframe::simple(90.0, 0.5)];
+
+        let result = CurvedNode::build(
+            &anchor, 20.0, 90.0, 0.0, 0.0, 0.0, false, false, RollChannel::Angle, &roll, &[], &[], &[], &[], &[],
+            &PropertyDomains::DEFAULT, 1.1, 0.0, 0.0,
+        );
+
+        approx::assert_relative_eq!(result.last().unwrap().roll(), 0.5, epsilon = 1e-2);
+    }
+
+    #[test]
+    fn curved_node_roll_keyed_by_time() {
+        let anchor = anchor();
+        let roll = [Keyframe::simple(0.0, 0.0), Keyframe::simple(2.0, 0.5)];
+        let build = |domains: &PropertyDomains| {
+            CurvedNode::build(
+                &anchor, 20.0, 90.0, 0.0, 0.0, 0.0, false, false, RollChannel::Angle, &roll, &[], &[], &[], &[], &[],
+                domains, 1.1, 0.0, 0.0,
+            )
+        };
+
+        let by_angle = build(&PropertyDomains::DEFAULT.with(PropertyId::Roll, CurveDomain::Angle));
+        let by_time = build(&PropertyDomains::DEFAULT.with(PropertyId::Roll, CurveDomain::Time));
+
+        assert_eq!(by_angle, build(&PropertyDomains::DEFAULT));
+        let one_second = sim::HZ as usize;
+        approx::assert_relative_eq!(by_time[one_second].roll(), 0.25, epsilon = 1e-2);
+        approx::assert_relative_eq!(by_angle[one_second].roll(), 0.5, epsilon = 1e-2);
+    }
+
     mod golden_tests {
         use super::*;
         use crate::golden::GoldTrackData;
@@ -384,11 +508,15 @@ mod tests {
                 curve_data.lead_in,
                 curve_data.lead_out,
                 overrides.map(|o| o.fixed_velocity).unwrap_or(false),
+                false,
+                RollChannel::Speed,
                 &roll_speed,
+                &[],
                 &fixed_velocity,
                 &heart_offset,
                 &friction,
                 &resistance,
+                &PropertyDomains::DEFAULT,
                 section.inputs.anchor.heart,
                 section.inputs.anchor.friction,
                 section.inputs.anchor.resistance,
@@ -0,0 +1,165 @@
+use kexedit_core::{sim, Keyframe, KeyframeCursor, Point};
+
+use crate::PropertyId;
+
+/// The parameter a property curve is keyed against.
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CurveDomain {
+    /// Whatever the node steps along: time or distance per its duration type,
+    /// and for curved sections the arc angle for roll and lateral force.
+    Section = 0,
+    /// Seconds since the section's anchor.
+    Time = 1,
+    /// Metres of heartline since the anchor.
+    HeartDistance = 2,
+    /// Metres of spine since the anchor.
+    SpineDistance = 3,
+    /// Degrees the track direction has turned since the anchor; the arc angle
+    /// in curved sections.
+    Angle = 4,
+}
+
+impl CurveDomain {
+    /// `native` if this is `Section`, otherwise this domain.
+    pub const fn resolve(self, native: CurveDomain) -> CurveDomain {
+        match self {
+            CurveDomain::Section => native,
+            domain => domain,
+        }
+    }
+}
+
+/// Domain of each property curve of a node. Only the curve's x-axis changes;
+/// values keep their units, so a roll speed in a distance section is still
+/// per metre when keyed by time.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PropertyDomains {
+    domains: [CurveDomain; PropertyId::COUNT],
+}
+
+impl PropertyDomains {
+    pub const DEFAULT: Self = Self {
+        domains: [CurveDomain::Section; PropertyId::COUNT],
+    };
+
+    pub const fn get(&self, property: PropertyId) -> CurveDomain {
+        self.domains[property as usize]
+    }
+
+    pub const fn with(mut self, property: PropertyId, domain: CurveDomain) -> Self {
+        self.domains[property as usize] = domain;
+        self
+    }
+}
+
+impl Default for PropertyDomains {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Where the step being built lies in every domain.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Location {
+    pub section: f32,
+    pub time: f32,
+    pub heart_distance: f32,
+    pub spine_distance: f32,
+    pub angle: f32,
+}
+
+impl Location {
+    pub const fn get(&self, domain: CurveDomain) -> f32 {
+        match domain {
+            CurveDomain::Section => self.section,
+            CurveDomain::Time => self.time,
+            CurveDomain::HeartDistance => self.heart_distance,
+            CurveDomain::SpineDistance => self.spine_distance,
+            CurveDomain::Angle => self.angle,
+        }
+    }
+}
+
+/// Tracks a section's progress from its anchor so each step can be located.
+pub(crate) struct Locator {
+    origin: Point,
+    angle: f32,
+}
+
+impl Locator {
+    pub(crate) const fn new(anchor: &Point) -> Self {
+        Self { origin: *anchor, angle: 0.0 }
+    }
+
+    /// Location of step `step` (the anchor is step 0) built from `prev`, with
+    /// distances projected one step ahead at `prev`'s velocity as distance
+    /// sections do.
+    pub(crate) fn locate(&self, prev: &Point, step: usize, section: f32) -> Location {
+        let ahead = prev.velocity / sim::HZ;
+        Location {
+            section,
+            time: step as f32 / sim::HZ,
+            heart_distance: prev.heart_arc - self.origin.heart_arc + ahead,
+            spine_distance: prev.spine_arc - self.origin.spine_arc + ahead,
+            angle: self.angle,
+        }
+    }
+
+    /// Accumulates the direction change from `prev` to `curr`.
+    pub(crate) fn advance(&mut self, prev: &Point, curr: &Point) {
+        self.angle += prev.direction.dot(curr.direction).clamp(-1.0, 1.0).acos().to_degrees();
+    }
+}
+
+/// A property curve evaluated in its declared domain.
+pub(crate) struct PropertyCurve<'a> {
+    cursor: KeyframeCursor<'a>,
+    domain: CurveDomain,
+}
+
+impl<'a> PropertyCurve<'a> {
+    pub(crate) const fn new(keyframes: &'a [Keyframe], domain: CurveDomain) -> Self {
+        Self {
+            cursor: KeyframeCursor::new(keyframes),
+            domain,
+        }
+    }
+
+    pub(crate) fn evaluate(&mut self, location: &Location, default_value: f32) -> f32 {
+        self.cursor.evaluate(location.get(self.domain), default_value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::anchor_at;
+    use kexedit_core::Float3;
+
+    #[test]
+    fn default_domains_are_section() {
+        let domains = PropertyDomains::default().with(PropertyId::Friction, CurveDomain::HeartDistance);
+
+        assert_eq!(domains.get(PropertyId::Friction), CurveDomain::HeartDistance);
+        assert_eq!(domains.get(PropertyId::Roll), CurveDomain::Section);
+        assert_eq!(CurveDomain::Section.resolve(CurveDomain::Angle), CurveDomain::Angle);
+        assert_eq!(CurveDomain::Time.resolve(CurveDomain::Angle), CurveDomain::Time);
+    }
+
+    #[test]
+    fn locator_tracks_distance_and_turned_angle() {
+        let anchor = anchor_at(10.0, 10.0);
+        let mut locator = Locator::new(&anchor);
+        let mut turned = anchor;
+        turned.direction = Float3::new(0.0, 1.0, -1.0).normalize();
+        locator.advance(&anchor, &turned);
+
+        let location = locator.locate(&anchor, 3, 7.0);
+
+        assert_eq!(location.section, 7.0);
+        assert_eq!(location.time, 3.0 / sim::HZ);
+        assert!((location.heart_distance - 0.1).abs() < 1e-6);
+        assert!((location.angle - 45.0).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,109 @@
+use std::f32::consts::PI;
+
+use kexedit_core::{sim, Point};
+
+/// A state that ends a section before its duration runs out. The duration
+/// stays as a limit in case the condition is never met.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum EndCondition {
+    /// Pitch in degrees.
+    Pitch(f32),
+    /// Heartline height in metres.
+    Height(f32),
+    /// Velocity in m/s.
+    Velocity(f32),
+    /// Heading in degrees, measured like `Frame::yaw`.
+    Yaw(f32),
+    /// Roll back to zero.
+    RollLevel,
+}
+
+impl EndCondition {
+    /// Whether the step from `prev` to `curr` reaches or crosses the condition.
+    /// Leaving a value the section started on does not count, and angles
+    /// wrapping past ±180° are not taken as crossings.
+    pub fn crossed(&self, prev: &Point, curr: &Point) -> bool {
+        let (before, after) = (self.offset(prev), self.offset(curr));
+        if before == 0.0 {
+            return false;
+        }
+        let wrapped = self.is_angle() && (after - before).abs() > PI;
+        after == 0.0 || (before.signum() != after.signum() && !wrapped)
+    }
+
+    const fn is_angle(&self) -> bool {
+        matches!(self, EndCondition::Pitch(_) | EndCondition::Yaw(_) | EndCondition::RollLevel)
+    }
+
+    /// Signed distance of `point` from the condition, angles in radians.
+    fn offset(&self, point: &Point) -> f32 {
+        match *self {
+            EndCondition::Pitch(pitch) => point.frame().pitch() - pitch.to_radians(),
+            EndCondition::Height(height) => point.heart_position.y - height,
+            EndCondition::Velocity(velocity) => point.velocity - velocity,
+            EndCondition::Yaw(yaw) => sim::wrap_angle(point.frame().yaw() - yaw.to_radians()),
+            EndCondition::RollLevel => point.roll(),
+        }
+    }
+}
+
+/// Why a section stopped stepping.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SectionEnd {
+    /// The full time or distance was built.
+    Duration,
+    /// The end condition was met at the last point.
+    Condition(EndCondition),
+    /// The train stalled, or its driven velocity dropped to zero.
+    Stalled,
+}
+
+#[derive(Debug, Clone)]
+pub struct SectionOutput {
+    pub points: Vec<Point>,
+    pub end: SectionEnd,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kexedit_core::Float3;
+
+    fn point(direction: Float3, roll: f32) -> Point {
+        Point::create(Float3::new(0.0, 10.0, 0.0), direction, roll, 10.0, 1.1, 0.0, 0.0)
+    }
+
+    #[test]
+    fn roll_level_is_met_crossing_zero_but_not_leaving_it() {
+        let condition = EndCondition::RollLevel;
+        let level = point(Float3::BACK, 0.0);
+        let left = point(Float3::BACK, 0.02);
+        let right = point(Float3::BACK, -0.01);
+
+        assert!(!condition.crossed(&level, &left));
+        assert!(condition.crossed(&left, &right));
+        assert!(!condition.crossed(&left, &left));
+    }
+
+    #[test]
+    fn velocity_jump_past_the_target_is_a_crossing() {
+        let condition = EndCondition::Velocity(10.0);
+        let slow = point(Float3::BACK, 0.0).with_velocity(5.0, 1.1, 0.0, true);
+        let fast = point(Float3::BACK, 0.0).with_velocity(20.0, 1.1, 0.0, true);
+
+        assert!(condition.crossed(&slow, &fast));
+        assert!(!condition.crossed(&fast, &fast));
+    }
+
+    #[test]
+    fn yaw_ignores_wrapping_behind_the_target() {
+        let condition = EndCondition::Yaw(0.0);
+        let behind_left = point(Float3::new(-0.01, 0.0, 1.0), 0.0);
+        let behind_right = point(Float3::new(0.01, 0.0, 1.0), 0.0);
+        let ahead_left = point(Float3::new(-0.01, 0.0, -1.0), 0.0);
+        let ahead_right = point(Float3::new(0.01, 0.0, -1.0), 0.0);
+
+        assert!(!condition.crossed(&behind_left, &behind_right));
+        assert!(condition.crossed(&ahead_left, &ahead_right));
+    }
+}
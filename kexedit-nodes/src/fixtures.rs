@@ -0,0 +1,14 @@
+use kexedit_core::{Float3, Point};
+
+/// Level anchor heading along -Z, `height` metres up at `velocity` m/s, with a
+/// 1.1 m heart offset and no friction or resistance. Energy is measured from the
+/// train's centre as the builders do.
+pub fn anchor_at(height: f32, velocity: f32) -> Point {
+    Point::create(Float3::new(0.0, height, 0.0), Float3::BACK, 0.0, velocity, 1.1, 0.0, 0.0)
+        .with_velocity(velocity, 1.1, 0.0, true)
+}
+
+/// The anchor most node tests start from: 10 m up at 15 m/s.
+pub fn anchor() -> Point {
+    anchor_at(10.0, 15.0)
+}
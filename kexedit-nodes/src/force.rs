@@ -1,6 +1,9 @@
 use kexedit_core::{sim, Curvature, Forces, Frame, Keyframe, PhysicsParams, Point};
 
-use crate::{DurationType, IterationConfig};
+use crate::domain::{Locator, PropertyCurve, PropertyDomains};
+use crate::roll::{roll_to_angle, RollChannel};
+use crate::end_condition::{EndCondition, SectionEnd, SectionOutput};
+use crate::{DurationType, IterationConfig, PropertyId};
 
 const MAX_ITERATIONS: usize = 1_000_000;
 
@@ -50,12 +53,16 @@ fn step_by_forces(
     Frame::new(new_direction, new_normal, new_lateral)
 }
 
+/// `entry_velocity` is the velocity the previous point was built with, before
+/// any driven or stall override of `prev`; the longitudinal force is measured
+/// from it so those changes are included.
 pub fn advance(
     prev: &Point,
     target_normal_force: f32,
     target_lateral_force: f32,
     physics: &PhysicsParams,
     roll_speed_val: f32,
+    entry_velocity: f32,
 ) -> Point {
     let prev_frame = prev.frame();
     let new_frame = step_by_forces(
@@ -117,6 +124,7 @@ pub fn advance(
         new_energy,
         forces.normal,
         forces.lateral,
+        sim::longitudinal_force(entry_velocity, new_velocity),
         new_heart_arc,
         new_spine_arc,
         spine_advance,
@@ -132,7 +140,8 @@ pub fn build(
     anchor: &Point,
     config: &IterationConfig,
     driven: bool,
-    roll_speed: &[Keyframe],
+    roll_channel: RollChannel,
+    roll: &[Keyframe],
     normal_force: &[Keyframe],
     lateral_force: &[Keyframe],
     driven_velocity: &[Keyframe],
@@ -143,70 +152,122 @@ pub fn build(
     anchor_friction: f32,
     anchor_resistance: f32,
 ) -> Vec<Point> {
+    build_section(
+        anchor,
+        config,
+        driven,
+        roll_channel,
+        roll,
+        normal_force,
+        lateral_force,
+        driven_velocity,
+        heart_offset,
+        friction,
+        resistance,
+        anchor_heart,
+        anchor_friction,
+        anchor_resistance,
+    )
+    .points
+}
+
+/// Like `build`, also reporting why the section ended, which may be
+/// `config.end_condition` before the duration runs out.
+#[allow(clippy::too_many_arguments)]
+pub fn build_section(
+    anchor: &Point,
+    config: &IterationConfig,
+    driven: bool,
+    roll_channel: RollChannel,
+    roll: &[Keyframe],
+    normal_force: &[Keyframe],
+    lateral_force: &[Keyframe],
+    driven_velocity: &[Keyframe],
+    heart_offset: &[Keyframe],
+    friction: &[Keyframe],
+    resistance: &[Keyframe],
+    anchor_heart: f32,
+    anchor_friction: f32,
+    anchor_resistance: f32,
+) -> SectionOutput {
     let mut result = Vec::new();
     result.push(*anchor);
 
     let mut state = *anchor;
 
-    match config.duration_type {
-        DurationType::Time => {
-            build_time_section(
-                config.duration,
-                driven,
-                roll_speed,
-                normal_force,
-                lateral_force,
-                driven_velocity,
-                heart_offset,
-                friction,
-                resistance,
-                anchor_heart,
-                anchor_friction,
-                anchor_resistance,
-                &mut state,
-                &mut result,
-            );
-        }
-        DurationType::Distance => {
-            build_distance_section(
-                config.duration,
-                driven,
-                anchor.spine_arc,
-                roll_speed,
-                normal_force,
-                lateral_force,
-                driven_velocity,
-                heart_offset,
-                friction,
-                resistance,
-                anchor_heart,
-                anchor_friction,
-                anchor_resistance,
-                &mut state,
-                &mut result,
-            );
-        }
-    }
+    let end = match config.duration_type {
+        DurationType::Time => build_time_section(
+            config.duration,
+            driven,
+            roll_channel,
+            roll,
+            normal_force,
+            lateral_force,
+            driven_velocity,
+            heart_offset,
+            friction,
+            resistance,
+            &config.domains,
+            anchor_heart,
+            anchor_friction,
+            anchor_resistance,
+            config.end_condition,
+            &mut state,
+            &mut result,
+        ),
+        DurationType::Distance => build_distance_section(
+            config.duration,
+            driven,
+            anchor.spine_arc,
+            roll_channel,
+            roll,
+            normal_force,
+            lateral_force,
+            driven_velocity,
+            heart_offset,
+            friction,
+            resistance,
+            &config.domains,
+            anchor_heart,
+            anchor_friction,
+            anchor_resistance,
+            config.end_condition,
+            &mut state,
+            &mut result,
+        ),
+    };
 
-    result
+    SectionOutput { points: result, end }
 }
 
 fn build_time_section(
     duration: f32,
     driven: bool,
-    roll_speed: &[Keyframe],
+    roll_channel: RollChannel,
+    roll: &[Keyframe],
     normal_force: &[Keyframe],
     lateral_force: &[Keyframe],
     driven_velocity: &[Keyframe],
     heart_offset: &[Keyframe],
     friction: &[Keyframe],
     resistance: &[Keyframe],
+    domains: &PropertyDomains,
     anchor_heart: f32,
     anchor_friction: f32,
     anchor_resistance: f32,
+    end_condition: Option<EndCondition>,
     state: &mut Point,
     result: &mut Vec<Point>,
-) {
+) -> SectionEnd {
+    let mut driven_velocity = PropertyCurve::new(driven_velocity, domains.get(PropertyId::DrivenVelocity));
+    let mut heart_offset = PropertyCurve::new(heart_offset, domains.get(PropertyId::HeartOffset));
+    let mut friction = PropertyCurve::new(friction, domains.get(PropertyId::Friction));
+    let mut resistance = PropertyCurve::new(resistance, domains.get(PropertyId::Resistance));
+    let mut normal_force = PropertyCurve::new(normal_force, domains.get(PropertyId::NormalForce));
+    let mut lateral_force = PropertyCurve::new(lateral_force, domains.get(PropertyId::LateralForce));
+    let mut roll = PropertyCurve::new(roll, domains.get(roll_channel.property()));
+    let mut locator = Locator::new(state);
+
     let mut prev_heart_offset = anchor_heart;
     let mut prev_friction = anchor_friction;
 
@@ -215,11 +276,12 @@ fn build_time_section(
         let t = i as f32 / sim::HZ;
 
         let mut prev = *state;
+        let here = locator.locate(&prev, i, t);
 
         if driven {
-            let velocity = kexedit_core::evaluate(driven_velocity, t, prev.velocity);
+            let velocity = driven_velocity.evaluate(&here, prev.velocity);
             if velocity < sim::MIN_VELOCITY {
-                break;
+                return SectionEnd::Stalled;
             }
             prev = prev.with_velocity(velocity, prev_heart_offset, prev_friction, true);
         } else if prev.velocity < sim::MIN_VELOCITY {
@@ -227,17 +289,25 @@ fn build_time_section(
                 prev =
                     prev.with_velocity(sim::MIN_VELOCITY, prev_heart_offset, prev_friction, true);
             } else {
-                break;
+                return SectionEnd::Stalled;
             }
         }
 
-        let heart_offset_val = kexedit_core::evaluate(heart_offset, t, anchor_heart);
-        let friction_val = kexedit_core::evaluate(friction, t, anchor_friction);
-        let resistance_val = kexedit_core::evaluate(resistance, t, anchor_resistance);
-
-        let target_normal_force = kexedit_core::evaluate(normal_force, t, 1.0);
-        let target_lateral_force = kexedit_core::evaluate(lateral_force, t, 0.0);
-        let roll_speed_val = kexedit_core::evaluate(roll_speed, t, 0.0);
+        let heart_offset_val = heart_offset.evaluate(&here, anchor_heart);
+        let friction_val = friction.evaluate(&here, anchor_friction);
+        let resistance_val = resistance.evaluate(&here, anchor_resistance);
+
+        let target_normal_force = normal_force.evaluate(&here, 1.0);
+        let target_lateral_force = lateral_force.evaluate(&here, 0.0);
+        let roll_speed_val = match roll_channel {
+            RollChannel::Speed => roll.evaluate(&here, 0.0),
+            RollChannel::Angle => {
+                let physics = PhysicsParams::new(heart_offset_val, friction_val, resistance_val, 0.0, driven);
+                let unrolled = advance(&prev, target_normal_force, target_lateral_force, &physics, 0.0, prev.velocity);
+                let delta_roll = roll_to_angle(&unrolled, roll.evaluate(&here, unrolled.roll()));
+                delta_roll * sim::HZ
+            }
+        };
         let delta_roll = roll_speed_val / sim::HZ;
 
         let physics = PhysicsParams::new(
@@ -253,32 +323,53 @@ fn build_time_section(
             target_lateral_force,
             &physics,
             roll_speed_val,
+            state.velocity,
         );
 
         result.push(curr);
+        locator.advance(state, &curr);
+        let ended = end_condition.filter(|condition| condition.crossed(state, &curr));
         *state = curr;
         prev_heart_offset = heart_offset_val;
         prev_friction = friction_val;
+
+        if let Some(condition) = ended {
+            return SectionEnd::Condition(condition);
+        }
     }
+
+    SectionEnd::Duration
 }
 
 fn build_distance_section(
     duration: f32,
     driven: bool,
     anchor_spine_arc: f32,
-    roll_speed: &[Keyframe],
+    roll_channel: RollChannel,
+    roll: &[Keyframe],
     normal_force: &[Keyframe],
     lateral_force: &[Keyframe],
     driven_velocity: &[Keyframe],
     heart_offset: &[Keyframe],
     friction: &[Keyframe],
     resistance: &[Keyframe],
+    domains: &PropertyDomains,
     anchor_heart: f32,
     anchor_friction: f32,
     anchor_resistance: f32,
+    end_condition: Option<EndCondition>,
     state: &mut Point,
     result: &mut Vec<Point>,
-) {
+) -> SectionEnd {
+    let mut driven_velocity = PropertyCurve::new(driven_velocity, domains.get(PropertyId::DrivenVelocity));
+    let mut heart_offset = PropertyCurve::new(heart_offset, domains.get(PropertyId::HeartOffset));
+    let mut friction = PropertyCurve::new(friction, domains.get(PropertyId::Friction));
+    let mut resistance = PropertyCurve::new(resistance, domains.get(PropertyId::Resistance));
+    let mut normal_force = PropertyCurve::new(normal_force, domains.get(PropertyId::NormalForce));
+    let mut lateral_force = PropertyCurve::new(lateral_force, domains.get(PropertyId::LateralForce));
+    let mut roll = PropertyCurve::new(roll, domains.get(roll_channel.property()));
+    let mut locator = Locator::new(state);
+
     let mut prev_heart_offset = anchor_heart;
     let mut prev_friction = anchor_friction;
 
@@ -293,12 +384,13 @@ fn build_distance_section(
 
         let prev = *state;
         let d = prev.spine_arc - anchor_spine_arc + prev.velocity / sim::HZ;
+        let here = locator.locate(&prev, iterations, d);
 
         let mut prev = prev;
         if driven {
-            let velocity = kexedit_core::evaluate(driven_velocity, d, prev.velocity);
+            let velocity = driven_velocity.evaluate(&here, prev.velocity);
             if velocity < sim::MIN_VELOCITY {
-                break;
+                return SectionEnd::Stalled;
             }
             prev = prev.with_velocity(velocity, prev_heart_offset, prev_friction, true);
         } else if prev.velocity < sim::MIN_VELOCITY {
@@ -306,17 +398,25 @@ fn build_distance_section(
                 prev =
                     prev.with_velocity(sim::MIN_VELOCITY, prev_heart_offset, prev_friction, true);
             } else {
-                break;
+                return SectionEnd::Stalled;
             }
         }
 
-        let heart_offset_val = kexedit_core::evaluate(heart_offset, d, anchor_heart);
-        let friction_val = kexedit_core::evaluate(friction, d, anchor_friction);
-        let resistance_val = kexedit_core::evaluate(resistance, d, anchor_resistance);
-
-        let target_normal_force = kexedit_core::evaluate(normal_force, d, 1.0);
-        let target_lateral_force = kexedit_core::evaluate(lateral_force, d, 0.0);
-        let roll_speed_val = kexedit_core::evaluate(roll_speed, d, 0.0);
+        let heart_offset_val = heart_offset.evaluate(&here, anchor_heart);
+        let friction_val = friction.evaluate(&here, anchor_friction);
+        let resistance_val = resistance.evaluate(&here, anchor_resistance);
+
+        let target_normal_force = normal_force.evaluate(&here, 1.0);
+        let target_lateral_force = lateral_force.evaluate(&here, 0.0);
+        let roll_speed_val = match roll_channel {
+            RollChannel::Speed => roll.evaluate(&here, 0.0),
+            RollChannel::Angle => {
+                let physics = PhysicsParams::new(heart_offset_val, friction_val, resistance_val, 0.0, driven);
+                let unrolled = advance(&prev, target_normal_force, target_lateral_force, &physics, 0.0, prev.velocity);
+                let delta_roll = roll_to_angle(&unrolled, roll.evaluate(&here, unrolled.roll()));
+                delta_roll / (prev.velocity / sim::HZ)
+            }
+        };
         let delta_roll = roll_speed_val * (prev.velocity / sim::HZ);
 
         let physics = PhysicsParams::new(
@@ -332,26 +432,37 @@ fn build_distance_section(
             target_lateral_force,
             &physics,
             roll_speed_val,
+            state.velocity,
         );
 
         result.push(curr);
+        locator.advance(state, &curr);
+        let ended = end_condition.filter(|condition| condition.crossed(state, &curr));
         *state = curr;
         prev_heart_offset = heart_offset_val;
         prev_friction = friction_val;
+
+        if let Some(condition) = ended {
+            return SectionEnd::Condition(condition);
+        }
     }
+
+    SectionEnd::Duration
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use kexedit_core::Float3;
+    use crate::fixtures::{anchor, anchor_at};
+    use crate::domain::CurveDomain;
+    use kexedit_core::{Float3, InterpolationType};
 
     #[test]
     fn advance_updates_position() {
         let anchor = Point::DEFAULT;
         let physics = PhysicsParams::new(1.1, 0.0, 0.0, 0.0, false);
 
-        let result = advance(&anchor, 1.0, 0.0, &physics, 0.0);
+        let result = advance(&anchor, 1.0, 0.0, &physics, 0.0, anchor.velocity);
 
         assert_ne!(result.heart_position, anchor.heart_position);
         assert!(result.heart_advance > 0.0);
@@ -370,7 +481,7 @@ mod tests {
         );
         let physics = PhysicsParams::new(1.1, 0.0, 0.0, 0.0, false);
 
-        let result = advance(&anchor, 1.0, 0.0, &physics, 0.0);
+        let result = advance(&anchor, 1.0, 0.0, &physics, 0.0, anchor.velocity);
 
         assert!(result.velocity > 0.0);
     }
@@ -384,6 +495,7 @@ mod tests {
             &anchor,
             &config,
             false,
+            RollChannel::Speed,
             &[],
             &[],
             &[],
@@ -409,6 +521,7 @@ mod tests {
             &anchor,
             &config,
             false,
+            RollChannel::Speed,
             &[],
             &[],
             &[],
@@ -442,6 +555,7 @@ mod tests {
             &slow_anchor,
             &config,
             false,
+            RollChannel::Speed,
             &[],
             &[],
             &[],
@@ -457,6 +571,152 @@ mod tests {
         assert!(result.len() <= (sim::HZ * config.duration).round() as usize);
     }
 
+    #[test]
+    fn build_driven_launch_records_longitudinal_force() {
+        let anchor = Point::DEFAULT;
+        let config = IterationConfig::new(1.0, DurationType::Time);
+        let launch = [
+            kexedit_core::Keyframe::new(
+                0.0,
+                10.0,
+                kexedit_core::InterpolationType::Linear,
+                kexedit_core::InterpolationType::Linear,
+                0.0,
+                0.0,
+                1.0 / 3.0,
+                1.0 / 3.0,
+            ),
+            kexedit_core::Keyframe::new(
+                1.0,
+                10.0 + sim::G,
+                kexedit_core::InterpolationType::Linear,
+                kexedit_core::InterpolationType::Linear,
+                0.0,
+                0.0,
+                1.0 / 3.0,
+                1.0 / 3.0,
+            ),
+        ];
+
+        let result = build(
+            &anchor,
+            &config,
+            true,
+            RollChannel::Speed,
+            &[],
+            &[],
+            &[],
+            &launch,
+            &[],
+            &[],
+            &[],
+            1.1,
+            0.0,
+            0.0,
+        );
+
+        for point in &result[2..] {
+            approx::assert_relative_eq!(point.longitudinal_force, 1.0, epsilon = 1e-2);
+        }
+    }
+
+    fn build_roll(anchor: &Point, config: &IterationConfig, roll_channel: RollChannel, roll: &[Keyframe]) -> Vec<Point> {
+        build(anchor, config, false, roll_channel, roll, &[], &[], &[], &[], &[], &[], 1.1, 0.0, 0.0)
+    }
+
+    #[test]
+    fn roll_angle_keyframes_set_bank() {
+        let anchor = anchor();
+
+        for config in [
+            IterationConfig::new(1.0, DurationType::Time),
+            IterationConfig::new(15.0, DurationType::Distance),
+        ] {
+            let roll = [Keyframe::simple(0.0, 0.0), Keyframe::simple(config.duration, 0.8)];
+            let result = build_roll(&anchor, &config, RollChannel::Angle, &roll);
+
+            let last = result.last().unwrap();
+            approx::assert_relative_eq!(last.roll(), 0.8, epsilon = 1e-2);
+            assert!(result.windows(2).all(|w| w[1].roll() >= w[0].roll() - 1e-4));
+        }
+    }
+
+    #[test]
+    fn empty_roll_angle_keyframes_match_zero_roll_speed() {
+        let anchor = Point::create(Float3::new(0.0, 10.0, 0.0), Float3::BACK, 0.3, 15.0, 1.1, 0.0, 0.0);
+        let config = IterationConfig::new(1.0, DurationType::Time);
+
+        let angle = build_roll(&anchor, &config, RollChannel::Angle, &[]);
+        let speed = build_roll(&anchor, &config, RollChannel::Speed, &[]);
+
+        assert_eq!(angle, speed);
+    }
+
+    #[test]
+    fn friction_keyed_by_heart_distance_in_time_section() {
+        let anchor = anchor_at(10.0, 12.0);
+        let friction = [Keyframe::simple(0.0, 0.0), Keyframe::simple(20.0, 0.04)];
+        let domains = PropertyDomains::DEFAULT.with(PropertyId::Friction, CurveDomain::HeartDistance);
+        let config = IterationConfig::new(2.0, DurationType::Time).with_domains(domains);
+
+        let result = build(
+            &anchor, &config, false, RollChannel::Speed, &[], &[], &[], &[], &[], &friction, &[], 1.1, 0.0, 0.0,
+        );
+
+        for pair in result.windows(2).skip(1) {
+            let distance = pair[0].heart_arc - anchor.heart_arc + pair[0].velocity / sim::HZ;
+            assert_eq!(pair[1].friction, kexedit_core::evaluate(&friction, distance, 0.0));
+        }
+        assert!(result.last().unwrap().friction > 0.0);
+    }
+
+    #[test]
+    fn pitch_end_condition_stops_section_once_crossed() {
+        let anchor = anchor_at(10.0, 20.0);
+        let condition = EndCondition::Pitch(45.0);
+        let config = IterationConfig::new(5.0, DurationType::Time).with_end_condition(condition);
+        let normal_force = [Keyframe::simple(0.0, 3.0)];
+
+        let output = build_section(
+            &anchor, &config, false, RollChannel::Speed, &[], &normal_force, &[], &[], &[], &[], &[], 1.1, 0.0, 0.0,
+        );
+        let pitch = |i: usize| output.points[i].frame().pitch().to_degrees();
+        let last = output.points.len() - 1;
+
+        assert_eq!(output.end, SectionEnd::Condition(condition));
+        assert!(pitch(last) >= 45.0);
+        assert!(pitch(last - 1) < 45.0);
+    }
+
+    #[test]
+    fn velocity_end_condition_catches_a_driven_jump() {
+        let anchor = anchor_at(10.0, 5.0);
+        let condition = EndCondition::Velocity(10.0);
+        let config = IterationConfig::new(2.0, DurationType::Time).with_end_condition(condition);
+        let driven_velocity = [
+            Keyframe::new(0.0, 5.0, InterpolationType::Constant, InterpolationType::Constant, 0.0, 0.0, 1.0 / 3.0, 1.0 / 3.0),
+            Keyframe::simple(0.5, 20.0),
+        ];
+
+        let output = build_section(
+            &anchor, &config, true, RollChannel::Speed, &[], &[], &[], &driven_velocity, &[], &[], &[], 1.1, 0.0, 0.0,
+        );
+
+        assert_eq!(output.end, SectionEnd::Condition(condition));
+        assert_eq!(output.points.last().unwrap().velocity, 20.0);
+    }
+
+    #[test]
+    fn unmet_end_condition_builds_full_duration() {
+        let anchor = anchor_at(10.0, 20.0);
+        let config = IterationConfig::new(20.0, DurationType::Distance).with_end_condition(EndCondition::Height(50.0));
+
+        let output = build_section(&anchor, &config, false, RollChannel::Speed, &[], &[], &[], &[], &[], &[], &[], 1.1, 0.0, 0.0);
+
+        assert_eq!(output.end, SectionEnd::Duration);
+        assert_eq!(output.points, build_roll(&anchor, &IterationConfig::new(20.0, DurationType::Distance), RollChannel::Speed, &[]));
+    }
+
     mod golden_tests {
         use super::*;
         use crate::golden::GoldTrackData;
@@ -495,6 +755,7 @@ mod tests {
                 &anchor,
                 &config,
                 overrides.map(|o| o.fixed_velocity).unwrap_or(false),
+                RollChannel::Speed,
                 &roll_speed,
                 &normal_force,
                 &lateral_force,
@@ -2,7 +2,10 @@ use kexedit_core::{
     sim, Curvature, Forces, Frame, Keyframe, Point, Float3,
 };
 
-use crate::{DurationType, IterationConfig};
+use crate::domain::{Locator, PropertyCurve, PropertyDomains};
+use crate::roll::{roll_to_angle, solve_bank, RollChannel};
+use crate::end_condition::{EndCondition, SectionEnd, SectionOutput};
+use crate::{DurationType, IterationConfig, PropertyId};
 
 const MAX_ITERATIONS: usize = 1_000_000;
 
@@ -11,9 +14,12 @@ pub fn build(
     config: &IterationConfig,
     driven: bool,
     steering: bool,
-    roll_speed: &[Keyframe],
+    auto_bank: bool,
+    roll_channel: RollChannel,
+    roll: &[Keyframe],
     pitch_speed: &[Keyframe],
     yaw_speed: &[Keyframe],
+    lateral_force: &[Keyframe],
     driven_velocity: &[Keyframe],
     heart_offset: &[Keyframe],
     friction: &[Keyframe],
@@ -22,57 +28,107 @@ pub fn build(
     anchor_friction: f32,
     anchor_resistance: f32,
 ) -> Vec<Point> {
+    build_section(
+        anchor,
+        config,
+        driven,
+        steering,
+        auto_bank,
+        roll_channel,
+        roll,
+        pitch_speed,
+        yaw_speed,
+        lateral_force,
+        driven_velocity,
+        heart_offset,
+        friction,
+        resistance,
+        anchor_heart,
+        anchor_friction,
+        anchor_resistance,
+    )
+    .points
+}
+
+/// Like `build`, also reporting why the section ended, which may be
+/// `config.end_condition` before the duration runs out.
+#[allow(clippy::too_many_arguments)]
+pub fn build_section(
+    anchor: &Point,
+    config: &IterationConfig,
+    driven: bool,
+    steering: bool,
+    auto_bank: bool,
+    roll_channel: RollChannel,
+    roll: &[Keyframe],
+    pitch_speed: &[Keyframe],
+    yaw_speed: &[Keyframe],
+    lateral_force: &[Keyframe],
+    driven_velocity: &[Keyframe],
+    heart_offset: &[Keyframe],
+    friction: &[Keyframe],
+    resistance: &[Keyframe],
+    anchor_heart: f32,
+    anchor_friction: f32,
+    anchor_resistance: f32,
+) -> SectionOutput {
     let mut result = Vec::new();
     result.push(*anchor);
 
     let mut state = *anchor;
     let mut accumulated_roll = 0.0;
 
-    match config.duration_type {
-        DurationType::Time => {
-            build_time_section(
-                config.duration,
-                driven,
-                steering,
-                roll_speed,
-                pitch_speed,
-                yaw_speed,
-                driven_velocity,
-                heart_offset,
-                friction,
-                resistance,
-                anchor_heart,
-                anchor_friction,
-                anchor_resistance,
-                &mut state,
-                &mut accumulated_roll,
-                &mut result,
-            );
-        }
-        DurationType::Distance => {
-            build_distance_section(
-                config.duration,
-                driven,
-                steering,
-                anchor.heart_arc,
-                roll_speed,
-                pitch_speed,
-                yaw_speed,
-                driven_velocity,
-                heart_offset,
-                friction,
-                resistance,
-                anchor_heart,
-                anchor_friction,
-                anchor_resistance,
-                &mut state,
-                &mut accumulated_roll,
-                &mut result,
-            );
-        }
-    }
+    let end = match config.duration_type {
+        DurationType::Time => build_time_section(
+            config.duration,
+            driven,
+            steering,
+            auto_bank,
+            roll_channel,
+            roll,
+            pitch_speed,
+            yaw_speed,
+            lateral_force,
+            driven_velocity,
+            heart_offset,
+            friction,
+            resistance,
+            &config.domains,
+            anchor_heart,
+            anchor_friction,
+            anchor_resistance,
+            config.end_condition,
+            &mut state,
+            &mut accumulated_roll,
+            &mut result,
+        ),
+        DurationType::Distance => build_distance_section(
+            config.duration,
+            driven,
+            steering,
+            auto_bank,
+            anchor.heart_arc,
+            roll_channel,
+            roll,
+            pitch_speed,
+            yaw_speed,
+            lateral_force,
+            driven_velocity,
+            heart_offset,
+            friction,
+            resistance,
+            &config.domains,
+            anchor_heart,
+            anchor_friction,
+            anchor_resistance,
+            config.end_condition,
+            &mut state,
+            &mut accumulated_roll,
+            &mut result,
+        ),
+    };
 
-    result
+    SectionOutput { points: result, end }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -80,20 +136,35 @@ fn build_time_section(
     duration: f32,
     driven: bool,
     steering: bool,
-    roll_speed: &[Keyframe],
+    auto_bank: bool,
+    roll_channel: RollChannel,
+    roll: &[Keyframe],
     pitch_speed: &[Keyframe],
     yaw_speed: &[Keyframe],
+    lateral_force: &[Keyframe],
     driven_velocity: &[Keyframe],
     heart_offset: &[Keyframe],
     friction: &[Keyframe],
     resistance: &[Keyframe],
+    domains: &PropertyDomains,
     anchor_heart: f32,
     anchor_friction: f32,
     anchor_resistance: f32,
+    end_condition: Option<EndCondition>,
     state: &mut Point,
     accumulated_roll: &mut f32,
     result: &mut Vec<Point>,
-) {
+) -> SectionEnd {
+    let mut driven_velocity = PropertyCurve::new(driven_velocity, domains.get(PropertyId::DrivenVelocity));
+    let mut heart_offset = PropertyCurve::new(heart_offset, domains.get(PropertyId::HeartOffset));
+    let mut friction = PropertyCurve::new(friction, domains.get(PropertyId::Friction));
+    let mut resistance = PropertyCurve::new(resistance, domains.get(PropertyId::Resistance));
+    let mut pitch_speed = PropertyCurve::new(pitch_speed, domains.get(PropertyId::PitchSpeed));
+    let mut yaw_speed = PropertyCurve::new(yaw_speed, domains.get(PropertyId::YawSpeed));
+    let mut lateral_force = PropertyCurve::new(lateral_force, domains.get(PropertyId::LateralForce));
+    let mut roll = PropertyCurve::new(roll, domains.get(roll_channel.property()));
+    let mut locator = Locator::new(state);
+
     let mut prev_heart_offset = anchor_heart;
     let mut prev_friction = anchor_friction;
 
@@ -102,33 +173,77 @@ fn build_time_section(
         let t = i as f32 / sim::HZ;
 
         let mut prev = *state;
+        let here = locator.locate(&prev, i, t);
 
         if driven {
-            let velocity = kexedit_core::evaluate(driven_velocity, t, prev.velocity);
+            let velocity = driven_velocity.evaluate(&here, prev.velocity);
             if velocity < sim::MIN_VELOCITY {
-                break;
+                return SectionEnd::Stalled;
             }
             prev = prev.with_velocity(velocity, prev_heart_offset, prev_friction, true);
         } else if prev.velocity < sim::MIN_VELOCITY {
             if prev.frame().pitch() < 0.0 {
                 prev = prev.with_velocity(sim::MIN_VELOCITY, prev_heart_offset, prev_friction, true);
             } else {
-                break;
+                return SectionEnd::Stalled;
             }
         }
 
-        let heart_offset_val = kexedit_core::evaluate(heart_offset, t, anchor_heart);
-        let friction_val = kexedit_core::evaluate(friction, t, anchor_friction);
-        let resistance_val = kexedit_core::evaluate(resistance, t, anchor_resistance);
+        let heart_offset_val = heart_offset.evaluate(&here, anchor_heart);
+        let friction_val = friction.evaluate(&here, anchor_friction);
+        let resistance_val = resistance.evaluate(&here, anchor_resistance);
 
-        let pitch_speed_val = kexedit_core::evaluate(pitch_speed, t, 0.0);
-        let yaw_speed_val = kexedit_core::evaluate(yaw_speed, t, 0.0);
-        let roll_speed_val = kexedit_core::evaluate(roll_speed, t, 0.0);
+        let pitch_speed_val = pitch_speed.evaluate(&here, 0.0);
+        let yaw_speed_val = yaw_speed.evaluate(&here, 0.0);
 
-        let delta_roll = roll_speed_val / sim::HZ;
         let delta_pitch = pitch_speed_val / sim::HZ;
         let delta_yaw = yaw_speed_val / sim::HZ;
 
+        let roll_speed_val = if auto_bank {
+            let target = lateral_force.evaluate(&here, 0.0);
+            let (delta_roll, _) = solve_bank(target, |delta_roll| {
+                let mut trial_roll = *accumulated_roll;
+                step_geometric(
+                    &prev,
+                    heart_offset_val,
+                    friction_val,
+                    resistance_val,
+                    delta_roll,
+                    delta_pitch,
+                    delta_yaw,
+                    prev.velocity / sim::HZ,
+                    driven,
+                    steering,
+                    0.0,
+                    &mut trial_roll,
+                    prev.velocity,
+                )
+            });
+            delta_roll * sim::HZ
+        } else if roll_channel == RollChannel::Angle {
+            let mut trial_roll = *accumulated_roll;
+            let unrolled = step_geometric(
+                &prev,
+                heart_offset_val,
+                friction_val,
+                resistance_val,
+                0.0,
+                delta_pitch,
+                delta_yaw,
+                prev.velocity / sim::HZ,
+                driven,
+                steering,
+                0.0,
+                &mut trial_roll,
+                prev.velocity,
+            );
+            let delta_roll = roll_to_angle(&unrolled, roll.evaluate(&here, unrolled.roll()));
+            delta_roll * sim::HZ
+        } else {
+            roll.evaluate(&here, 0.0)
+        };
+        let delta_roll = roll_speed_val / sim::HZ;
+
         let curr = step_geometric(
             &prev,
             heart_offset_val,
@@ -137,17 +252,27 @@ fn build_time_section(
             delta_roll,
             delta_pitch,
             delta_yaw,
+            prev.velocity / sim::HZ,
             driven,
             steering,
             roll_speed_val,
             accumulated_roll,
+            state.velocity,
         );
 
         result.push(curr);
+        locator.advance(state, &curr);
+        let ended = end_condition.filter(|condition| condition.crossed(state, &curr));
         *state = curr;
         prev_heart_offset = heart_offset_val;
         prev_friction = friction_val;
+
+        if let Some(condition) = ended {
+            return SectionEnd::Condition(condition);
+        }
     }
+
+    SectionEnd::Duration
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -155,21 +280,36 @@ fn build_distance_section(
     duration: f32,
     driven: bool,
     steering: bool,
+    auto_bank: bool,
     anchor_heart_arc: f32,
-    roll_speed: &[Keyframe],
+    roll_channel: RollChannel,
+    roll: &[Keyframe],
     pitch_speed: &[Keyframe],
     yaw_speed: &[Keyframe],
+    lateral_force: &[Keyframe],
     driven_velocity: &[Keyframe],
     heart_offset: &[Keyframe],
     friction: &[Keyframe],
     resistance: &[Keyframe],
+    domains: &PropertyDomains,
     anchor_heart: f32,
     anchor_friction: f32,
     anchor_resistance: f32,
+    end_condition: Option<EndCondition>,
     state: &mut Point,
     accumulated_roll: &mut f32,
     result: &mut Vec<Point>,
-) {
+) -> SectionEnd {
+    let mut driven_velocity = PropertyCurve::new(driven_velocity, domains.get(PropertyId::DrivenVelocity));
+    let mut heart_offset = PropertyCurve::new(heart_offset, domains.get(PropertyId::HeartOffset));
+    let mut friction = PropertyCurve::new(friction, domains.get(PropertyId::Friction));
+    let mut resistance = PropertyCurve::new(resistance, domains.get(PropertyId::Resistance));
+    let mut pitch_speed = PropertyCurve::new(pitch_speed, domains.get(PropertyId::PitchSpeed));
+    let mut yaw_speed = PropertyCurve::new(yaw_speed, domains.get(PropertyId::YawSpeed));
+    let mut lateral_force = PropertyCurve::new(lateral_force, domains.get(PropertyId::LateralForce));
+    let mut roll = PropertyCurve::new(roll, domains.get(roll_channel.property()));
+    let mut locator = Locator::new(state);
+
     let mut prev_heart_offset = anchor_heart;
     let mut prev_friction = anchor_friction;
 
@@ -184,34 +324,78 @@ fn build_distance_section(
 
         let prev = *state;
         let d = prev.heart_arc - anchor_heart_arc + prev.velocity / sim::HZ;
+        let here = locator.locate(&prev, iterations, d);
 
         let mut prev = prev;
         if driven {
-            let velocity = kexedit_core::evaluate(driven_velocity, d, prev.velocity);
+            let velocity = driven_velocity.evaluate(&here, prev.velocity);
             if velocity < sim::MIN_VELOCITY {
-                break;
+                return SectionEnd::Stalled;
             }
             prev = prev.with_velocity(velocity, prev_heart_offset, prev_friction, true);
         } else if prev.velocity < sim::MIN_VELOCITY {
             if prev.frame().pitch() < 0.0 {
                 prev = prev.with_velocity(sim::MIN_VELOCITY, prev_heart_offset, prev_friction, true);
             } else {
-                break;
+                return SectionEnd::Stalled;
             }
         }
 
-        let heart_offset_val = kexedit_core::evaluate(heart_offset, d, anchor_heart);
-        let friction_val = kexedit_core::evaluate(friction, d, anchor_friction);
-        let resistance_val = kexedit_core::evaluate(resistance, d, anchor_resistance);
+        let heart_offset_val = heart_offset.evaluate(&here, anchor_heart);
+        let friction_val = friction.evaluate(&here, anchor_friction);
+        let resistance_val = resistance.evaluate(&here, anchor_resistance);
 
-        let pitch_speed_val = kexedit_core::evaluate(pitch_speed, d, 0.0);
-        let yaw_speed_val = kexedit_core::evaluate(yaw_speed, d, 0.0);
-        let roll_speed_val = kexedit_core::evaluate(roll_speed, d, 0.0);
+        let pitch_speed_val = pitch_speed.evaluate(&here, 0.0);
+        let yaw_speed_val = yaw_speed.evaluate(&here, 0.0);
 
-        let delta_roll = roll_speed_val * (prev.velocity / sim::HZ);
         let delta_pitch = pitch_speed_val * (prev.velocity / sim::HZ);
         let delta_yaw = yaw_speed_val * (prev.velocity / sim::HZ);
 
+        let roll_speed_val = if auto_bank {
+            let target = lateral_force.evaluate(&here, 0.0);
+            let (delta_roll, _) = solve_bank(target, |delta_roll| {
+                let mut trial_roll = *accumulated_roll;
+                step_geometric(
+                    &prev,
+                    heart_offset_val,
+                    friction_val,
+                    resistance_val,
+                    delta_roll,
+                    delta_pitch,
+                    delta_yaw,
+                    prev.velocity / sim::HZ,
+                    driven,
+                    steering,
+                    0.0,
+                    &mut trial_roll,
+                    prev.velocity,
+                )
+            });
+            delta_roll / (prev.velocity / sim::HZ)
+        } else if roll_channel == RollChannel::Angle {
+            let mut trial_roll = *accumulated_roll;
+            let unrolled = step_geometric(
+                &prev,
+                heart_offset_val,
+                friction_val,
+                resistance_val,
+                0.0,
+                delta_pitch,
+                delta_yaw,
+                prev.velocity / sim::HZ,
+                driven,
+                steering,
+                0.0,
+                &mut trial_roll,
+                prev.velocity,
+            );
+            let delta_roll = roll_to_angle(&unrolled, roll.evaluate(&here, unrolled.roll()));
+            delta_roll / (prev.velocity / sim::HZ)
+        } else {
+            roll.evaluate(&here, 0.0)
+        };
+        let delta_roll = roll_speed_val * (prev.velocity / sim::HZ);
+
         let curr = step_geometric(
             &prev,
             heart_offset_val,
@@ -220,21 +404,37 @@ fn build_distance_section(
             delta_roll,
             delta_pitch,
             delta_yaw,
+            prev.velocity / sim::HZ,
             driven,
             steering,
             roll_speed_val,
             accumulated_roll,
+            state.velocity,
         );
 
         result.push(curr);
+        locator.advance(state, &curr);
+        let ended = end_condition.filter(|condition| condition.crossed(state, &curr));
         *state = curr;
         prev_heart_offset = heart_offset_val;
         prev_friction = friction_val;
+
+        if let Some(condition) = ended {
+            return SectionEnd::Condition(condition);
+        }
     }
+
+    SectionEnd::Duration
 }
 
+/// `step_length` is the heartline distance to advance, `prev.velocity / sim::HZ`
+/// except where a section shortens its last step.
+///
+/// `entry_velocity` is the velocity the previous point was built with, before
+/// any driven or stall override of `prev`; the longitudinal force is measured
+/// from it so those changes are included.
 #[allow(clippy::too_many_arguments)]
-fn step_geometric(
+pub(crate) fn step_geometric(
     prev: &Point,
     heart_offset_val: f32,
     friction_val: f32,
@@ -242,10 +442,12 @@ fn step_geometric(
     delta_roll: f32,
     delta_pitch: f32,
     delta_yaw: f32,
+    step_length: f32,
     driven: bool,
     steering: bool,
     roll_speed_val: f32,
     accumulated_roll: &mut f32,
+    entry_velocity: f32,
 ) -> Point {
     let prev_frame = prev.frame();
     let prev_direction = prev.direction;
@@ -270,7 +472,7 @@ fn step_geometric(
         let lateral_unrolled = rotated.lateral;
         let normal_unrolled = rotated.normal;
 
-        let half_step_distance = prev.velocity / (2.0 * sim::HZ);
+        let half_step_distance = 0.5 * step_length;
         let curr_heart_position = prev_heart_position
             + curr_direction * half_step_distance
             + prev_direction * half_step_distance;
@@ -291,7 +493,7 @@ fn step_geometric(
         let curr_direction = rotated.direction;
         let mut curr_normal = rotated.normal;
 
-        let half_step_distance = prev.velocity / (2.0 * sim::HZ);
+        let half_step_distance = 0.5 * step_length;
         let prev_spine_pos = prev.spine_position(heart_offset_val);
         let curr_spine_pos_if_heart_static = prev_heart_position + curr_normal * heart_offset_val;
 
@@ -341,6 +543,7 @@ fn step_geometric(
         new_energy,
         forces.normal,
         forces.lateral,
+        sim::longitudinal_force(entry_velocity, new_velocity),
         new_heart_arc,
         new_spine_arc,
         heart_advance,
@@ -355,6 +558,7 @@ fn step_geometric(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixtures::anchor;
 
     #[test]
     fn build_time_section_creates_points() {
@@ -366,6 +570,9 @@ mod tests {
             &config,
             false,
             false,
+            false,
+            RollChannel::Speed,
+            &[],
             &[],
             &[],
             &[],
@@ -392,6 +599,9 @@ mod tests {
             &config,
             false,
             false,
+            false,
+            RollChannel::Speed,
+            &[],
             &[],
             &[],
             &[],
@@ -418,6 +628,9 @@ mod tests {
             &config,
             false,
             true,
+            false,
+            RollChannel::Speed,
+            &[],
             &[],
             &[],
             &[],
@@ -447,10 +660,12 @@ mod tests {
             0.0,
             0.0,
             0.0,
+            anchor.velocity / sim::HZ,
             false,
             false,
             0.0,
             &mut accumulated_roll,
+            anchor.velocity,
         );
 
         assert_ne!(result.heart_position, anchor.heart_position);
@@ -469,16 +684,156 @@ mod tests {
             0.1,
             0.0,
             0.0,
+            anchor.velocity / sim::HZ,
             false,
             true,
             0.1,
             &mut accumulated_roll,
+            anchor.velocity,
         );
 
         assert_ne!(result.heart_position, anchor.heart_position);
         assert!(accumulated_roll.abs() > 0.0);
     }
 
+    #[test]
+    fn build_auto_bank_zeroes_lateral_force() {
+        let anchor = anchor();
+        let config = IterationConfig::new(2.0, DurationType::Time);
+        let yaw_speed = [Keyframe::simple(0.0, 0.5)];
+
+        let result = build(
+            &anchor,
+            &config,
+            false,
+            false,
+            true,
+            RollChannel::Speed,
+            &[],
+            &[],
+            &yaw_speed,
+            &[],
+            &[],
+            &[],
+            &[],
+            &[],
+            1.1,
+            0.0,
+            0.0,
+        );
+
+        for point in &result[1..] {
+            assert!(point.lateral_force.abs() < 1e-3, "lateral force {}", point.lateral_force);
+        }
+        assert!(result.last().unwrap().roll().abs() > 0.3);
+    }
+
+    #[test]
+    fn build_auto_bank_follows_lateral_force_target() {
+        let anchor = anchor();
+        let config = IterationConfig::new(20.0, DurationType::Distance);
+        let yaw_speed = [Keyframe::simple(0.0, 0.03)];
+        let lateral_force = [Keyframe::simple(0.0, 0.2)];
+
+        let result = build(
+            &anchor,
+            &config,
+            false,
+            true,
+            true,
+            RollChannel::Speed,
+            &[],
+            &[],
+            &yaw_speed,
+            &lateral_force,
+            &[],
+            &[],
+            &[],
+            &[],
+            1.1,
+            0.0,
+            0.0,
+        );
+
+        for point in &result[1..] {
+            assert!((point.lateral_force - 0.2).abs() < 1e-3, "lateral force {}", point.lateral_force);
+        }
+    }
+
+    #[test]
+    fn exported_roll_speed_rebuilds_auto_banked_section() {
+        let anchor = anchor();
+        let config = IterationConfig::new(2.0, DurationType::Time);
+        let yaw_speed = [Keyframe::simple(0.0, 0.0), Keyframe::simple(2.0, 0.6)];
+        let build_with = |auto_bank: bool, roll_speed: &[Keyframe]| {
+            build(
+                &anchor, &config, false, false, auto_bank, RollChannel::Speed, roll_speed, &[], &yaw_speed, &[], &[], &[], &[], &[], 1.1,
+                0.0, 0.0,
+            )
+        };
+
+        let banked = build_with(true, &[]);
+        let keys = crate::roll::roll_speed_keyframes(&banked, crate::domain::CurveDomain::Time, 1e-3);
+        let rebuilt = build_with(false, &keys);
+
+        assert_eq!(rebuilt.len(), banked.len());
+        let (a, b) = (banked.last().unwrap(), rebuilt.last().unwrap());
+        assert!((a.roll() - b.roll()).abs() < 1e-2);
+        assert!(b.lateral_force.abs() < 2e-2);
+    }
+
+    #[test]
+    fn roll_angle_keyframes_set_bank() {
+        let anchor = anchor();
+
+        for config in [
+            IterationConfig::new(2.0, DurationType::Time),
+            IterationConfig::new(30.0, DurationType::Distance),
+        ] {
+            let roll = [Keyframe::simple(0.0, 0.0), Keyframe::simple(config.duration, -0.7)];
+            let yaw_speed = [Keyframe::simple(0.0, 0.3)];
+            let result = build(
+                &anchor, &config, false, false, false, RollChannel::Angle, &roll, &[], &yaw_speed, &[], &[], &[], &[],
+                &[], 1.1, 0.0, 0.0,
+            );
+
+            approx::assert_relative_eq!(result.last().unwrap().roll(), -0.7, epsilon = 1e-2);
+        }
+    }
+
+    #[test]
+    fn yaw_end_condition_ends_distance_section() {
+        let anchor = anchor();
+        let condition = EndCondition::Yaw(90.0);
+        let config = IterationConfig::new(100.0, DurationType::Distance).with_end_condition(condition);
+        let yaw_speed = [Keyframe::simple(0.0, 0.05)];
+
+        let output = build_section(
+            &anchor, &config, true, false, false, RollChannel::Speed, &[], &[], &yaw_speed, &[], &[], &[], &[], &[], 1.1, 0.0,
+            0.0,
+        );
+        let yaw = |i: usize| output.points[i].frame().yaw().to_degrees();
+        let last = output.points.len() - 1;
+
+        assert_eq!(output.end, SectionEnd::Condition(condition));
+        assert!(yaw(last) >= 90.0);
+        assert!(yaw(last - 1) < 90.0);
+        assert!(output.points[last].heart_arc < 40.0);
+    }
+
+    #[test]
+    fn stalling_on_a_climb_is_reported() {
+        let anchor = Point::create(Float3::new(0.0, 10.0, 0.0), Float3::new(0.0, 1.0, -1.0), 0.0, 5.0, 1.1, 0.0, 0.0);
+        let config = IterationConfig::new(10.0, DurationType::Time);
+
+        let output = build_section(
+            &anchor, &config, false, false, false, RollChannel::Speed, &[], &[], &[], &[], &[], &[], &[], &[], 1.1, 0.0, 0.0,
+        );
+
+        assert_eq!(output.end, SectionEnd::Stalled);
+        assert!(output.points.len() < 1000);
+    }
+
     mod golden_tests {
         use super::*;
         use crate::golden::GoldTrackData;
@@ -521,9 +876,12 @@ mod tests {
                 &config,
                 overrides.map(|o| o.fixed_velocity).unwrap_or(false),
                 section.inputs.steering,
+                false,
+                RollChannel::Speed,
                 &roll_speed,
                 &pitch_speed,
                 &yaw_speed,
+                &[],
                 &fixed_velocity,
                 &heart_offset,
                 &friction,
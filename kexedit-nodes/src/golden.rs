@@ -1,4 +1,4 @@
-use kexedit_core::{Float3, InterpolationType, Point};
+use kexedit_core::{Float3, HandleType, InterpolationType, Point};
 use serde::{Deserialize, Serialize};
 
 use crate::DurationType;
@@ -247,6 +247,7 @@ impl GoldPointData {
             self.energy,
             self.normal_force,
             self.lateral_force,
+            0.0,
             self.total_length,
             self.total_heart_length,
             self.heart_distance_from_last,
@@ -271,6 +272,7 @@ impl GoldKeyframe {
             self.in_weight,
             self.out_weight,
         )
+        .with_handle_type(parse_handle_type(&self.handle_type))
     }
 }
 
@@ -284,11 +286,24 @@ impl GoldDuration {
     }
 }
 
+fn parse_handle_type(s: &str) -> HandleType {
+    match s {
+        "Aligned" => HandleType::Aligned,
+        "Auto" => HandleType::Auto,
+        "Vector" | "Linear" => HandleType::Vector,
+        _ => HandleType::Free,
+    }
+}
+
 fn parse_interpolation_type(s: &str) -> InterpolationType {
     match s {
         "Constant" => InterpolationType::Constant,
         "Linear" => InterpolationType::Linear,
         "Bezier" => InterpolationType::Bezier,
+        "Cosine" => InterpolationType::Cosine,
+        "Sinusoidal" => InterpolationType::Sinusoidal,
+        "Quintic" => InterpolationType::Quintic,
+        "Plateau" => InterpolationType::Plateau,
         _ => InterpolationType::Bezier,
     }
 }
@@ -334,4 +349,19 @@ mod tests {
         let geometric_sections = data.get_geometric_sections();
         assert!(!geometric_sections.is_empty());
     }
+
+    #[test]
+    fn test_keyframes_keep_handle_type() {
+        let data = GoldTrackData::load("../test-data/shuttle.json").unwrap();
+        let handle_types: Vec<HandleType> = data
+            .sections
+            .iter()
+            .filter_map(|s| s.inputs.keyframes.as_ref())
+            .flat_map(|k| k.normal_force.iter().chain(&k.fixed_velocity))
+            .map(|k| k.to_keyframe().handle_type)
+            .collect();
+
+        assert!(handle_types.contains(&HandleType::Aligned));
+        assert!(handle_types.contains(&HandleType::Free));
+    }
 }
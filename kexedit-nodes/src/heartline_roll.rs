@@ -0,0 +1,255 @@
+use kexedit_core::{sim, InterpolationType, Keyframe, PhysicsParams, Point};
+
+use crate::domain::{CurveDomain, Locator, PropertyCurve};
+use crate::end_condition::{SectionEnd, SectionOutput};
+use crate::force::advance;
+use crate::{DurationType, IterationConfig, PropertyId};
+
+const MAX_ITERATIONS: usize = 1_000_000;
+
+/// Roll speed keys that turn through `roll` degrees over `duration`, easing
+/// from zero up to a peak at the midpoint and back with cosine segments. The
+/// keys are in radians per unit of the section's duration type.
+pub fn roll_speed_keyframes(roll: f32, duration: f32) -> Vec<Keyframe> {
+    // Each cosine half averages half the peak, so the peak is twice the mean rate.
+    let peak = 2.0 * roll.to_radians() / duration;
+    let key = |time, value| {
+        Keyframe::new(time, value, InterpolationType::Cosine, InterpolationType::Cosine, 0.0, 0.0, 1.0 / 3.0, 1.0 / 3.0)
+    };
+    vec![key(0.0, 0.0), key(0.5 * duration, peak), key(duration, 0.0)]
+}
+
+/// Roll about the heartline, such as a zero-g roll. The path follows the normal
+/// force profile as in a force section while the generated roll speed turns
+/// the track through the requested angle about the rider's heart.
+///
+/// `force::advance` moves the spine at the train's velocity and hangs the
+/// heart below it, so rolling would swing the heart around the spine. Here the
+/// heartline is stepped with no offset, the train's mass taken at the heart,
+/// and the spine is hung from it afterwards, so the heart follows the path the
+/// forces alone give whatever the roll.
+pub struct HeartlineRollNode;
+
+impl HeartlineRollNode {
+    /// `roll` is in degrees, positive rolling the same way as a positive roll
+    /// speed. Distance sections are measured along the heartline. Normal force
+    /// defaults to 1 G; key it to 0 for a zero-g roll.
+    #[allow(clippy::too_many_arguments)]
+    pub fn build(
+        anchor: &Point,
+        config: &IterationConfig,
+        roll: f32,
+        driven: bool,
+        normal_force: &[Keyframe],
+        driven_velocity: &[Keyframe],
+        heart_offset: &[Keyframe],
+        friction: &[Keyframe],
+        resistance: &[Keyframe],
+        anchor_heart: f32,
+        anchor_friction: f32,
+        anchor_resistance: f32,
+    ) -> Vec<Point> {
+        Self::build_section(
+            anchor,
+            config,
+            roll,
+            driven,
+            normal_force,
+            driven_velocity,
+            heart_offset,
+            friction,
+            resistance,
+            anchor_heart,
+            anchor_friction,
+            anchor_resistance,
+        )
+        .points
+    }
+
+    /// Like `build`, also reporting why the section ended, which may be
+    /// `config.end_condition` before the roll is complete.
+    #[allow(clippy::too_many_arguments)]
+    pub fn build_section(
+        anchor: &Point,
+        config: &IterationConfig,
+        roll: f32,
+        driven: bool,
+        normal_force: &[Keyframe],
+        driven_velocity: &[Keyframe],
+        heart_offset: &[Keyframe],
+        friction: &[Keyframe],
+        resistance: &[Keyframe],
+        anchor_heart: f32,
+        anchor_friction: f32,
+        anchor_resistance: f32,
+    ) -> SectionOutput {
+        let roll_speed = roll_speed_keyframes(roll, config.duration);
+        let domains = &config.domains;
+        let mut roll_speed = PropertyCurve::new(&roll_speed, CurveDomain::Section);
+        let mut normal_force = PropertyCurve::new(normal_force, domains.get(PropertyId::NormalForce));
+        let mut driven_velocity = PropertyCurve::new(driven_velocity, domains.get(PropertyId::DrivenVelocity));
+        let mut heart_offset = PropertyCurve::new(heart_offset, domains.get(PropertyId::HeartOffset));
+        let mut friction = PropertyCurve::new(friction, domains.get(PropertyId::Friction));
+        let mut resistance = PropertyCurve::new(resistance, domains.get(PropertyId::Resistance));
+        let mut locator = Locator::new(anchor);
+
+        let mut result = Vec::new();
+        result.push(*anchor);
+
+        let mut state = *anchor;
+        state.energy -= center_energy(anchor, anchor_heart);
+        let mut prev_friction = anchor_friction;
+        let point_count = (sim::HZ * config.duration).floor() as usize;
+
+        let end = loop {
+            let index = result.len();
+            let travelled = state.heart_arc - anchor.heart_arc;
+            let section = match config.duration_type {
+                DurationType::Time if index < point_count => index as f32 / sim::HZ,
+                DurationType::Distance if travelled < config.duration => travelled + state.velocity / sim::HZ,
+                _ => break SectionEnd::Duration,
+            };
+            if index > MAX_ITERATIONS {
+                break SectionEnd::Duration;
+            }
+
+            let mut prev = state;
+            let here = locator.locate(&prev, index, section);
+
+            if driven {
+                let velocity = driven_velocity.evaluate(&here, prev.velocity);
+                if velocity < sim::MIN_VELOCITY {
+                    break SectionEnd::Stalled;
+                }
+                prev = prev.with_velocity(velocity, 0.0, prev_friction, true);
+            } else if prev.velocity < sim::MIN_VELOCITY {
+                if prev.frame().pitch() < 0.0 {
+                    prev = prev.with_velocity(sim::MIN_VELOCITY, 0.0, prev_friction, true);
+                } else {
+                    break SectionEnd::Stalled;
+                }
+            }
+
+            let heart_offset_val = heart_offset.evaluate(&here, anchor_heart);
+            let friction_val = friction.evaluate(&here, anchor_friction);
+            let resistance_val = resistance.evaluate(&here, anchor_resistance);
+
+            let target_normal_force = normal_force.evaluate(&here, 1.0);
+            let roll_speed_val = roll_speed.evaluate(&here, 0.0);
+            let delta_roll = match config.duration_type {
+                DurationType::Time => roll_speed_val / sim::HZ,
+                DurationType::Distance => roll_speed_val * (prev.velocity / sim::HZ),
+            };
+
+            let physics = PhysicsParams::new(0.0, friction_val, resistance_val, delta_roll, driven);
+            let mut curr = advance(&prev, target_normal_force, 0.0, &physics, roll_speed_val, state.velocity);
+            curr.heart_offset = heart_offset_val;
+
+            result.push(curr);
+            locator.advance(&state, &curr);
+            let ended = config.end_condition.filter(|condition| condition.crossed(&state, &curr));
+            state = curr;
+            prev_friction = friction_val;
+
+            if let Some(condition) = ended {
+                break SectionEnd::Condition(condition);
+            }
+        };
+
+        hang_spine(&mut result);
+        SectionOutput { points: result, end }
+    }
+}
+
+/// Energy of the train's centre of mass hanging below the heart rather than
+/// sitting on it, matching `Point::with_velocity`.
+fn center_energy(point: &Point, heart_offset: f32) -> f32 {
+    sim::G * 0.9 * heart_offset * point.normal.y
+}
+
+/// Restores the spine of points stepped along the heartline: spine arc and
+/// advance follow each point's heart offset, and energy is moved back to the
+/// centre of mass so following sections continue at the same velocity.
+fn hang_spine(points: &mut [Point]) {
+    for i in 1..points.len() {
+        let prev = points[i - 1];
+        let point = &mut points[i];
+        let spine_advance = (point.spine_position(point.heart_offset) - prev.spine_position(prev.heart_offset)).magnitude();
+        point.spine_arc = prev.spine_arc + spine_advance;
+        point.heart_advance = spine_advance;
+        point.energy += center_energy(point, point.heart_offset);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::end_condition::EndCondition;
+    use crate::fixtures::anchor_at;
+    use approx::assert_relative_eq;
+    use kexedit_core::integrate;
+
+    fn zero_g() -> [Keyframe; 1] {
+        [Keyframe::simple(0.0, 0.0)]
+    }
+
+    #[test]
+    fn roll_speed_keys_integrate_to_roll() {
+        let keys = roll_speed_keyframes(270.0, 3.0);
+
+        assert_relative_eq!(integrate(&keys, 0.0, 3.0, 0.0), 270f32.to_radians(), epsilon = 1e-4);
+        assert_eq!(keys[0].value, 0.0);
+        assert_eq!(keys[2].value, 0.0);
+    }
+
+    #[test]
+    fn time_section_rolls_through_requested_angle() {
+        let config = IterationConfig::new(2.0, DurationType::Time);
+
+        let result = HeartlineRollNode::build(&anchor_at(20.0, 15.0), &config, 90.0, false, &zero_g(), &[], &[], &[], &[], 1.1, 0.0, 0.0);
+        let last = result.last().unwrap();
+
+        assert_relative_eq!(last.roll().abs(), 90f32.to_radians(), epsilon = 1e-2);
+        let continued = last.with_velocity(last.velocity, 1.1, 0.0, false);
+        assert_relative_eq!(continued.energy, last.energy, epsilon = 1e-3);
+    }
+
+    #[test]
+    fn distance_section_rolls_through_requested_angle() {
+        let config = IterationConfig::new(40.0, DurationType::Distance);
+
+        let result = HeartlineRollNode::build(&anchor_at(20.0, 15.0), &config, -120.0, true, &zero_g(), &[], &[], &[], &[], 1.1, 0.0, 0.0);
+
+        assert_relative_eq!(result.last().unwrap().roll().abs(), 120f32.to_radians(), epsilon = 1e-2);
+    }
+
+    #[test]
+    fn end_condition_stops_the_roll_early() {
+        let condition = EndCondition::Height(18.0);
+        let config = IterationConfig::new(3.0, DurationType::Time).with_end_condition(condition);
+
+        let output = HeartlineRollNode::build_section(
+            &anchor_at(20.0, 15.0), &config, 180.0, false, &zero_g(), &[], &[], &[], &[], 1.1, 0.0, 0.0,
+        );
+        let points = &output.points;
+
+        assert_eq!(output.end, SectionEnd::Condition(condition));
+        assert!(points.len() < 3 * sim::HZ as usize - 1);
+        assert!(points[points.len() - 1].heart_position.y <= 18.0);
+        assert!(points[points.len() - 2].heart_position.y > 18.0);
+    }
+
+    #[test]
+    fn zero_g_roll_keeps_heart_on_unrolled_path() {
+        let config = IterationConfig::new(2.0, DurationType::Time);
+
+        let rolled = HeartlineRollNode::build(&anchor_at(20.0, 15.0), &config, 360.0, false, &zero_g(), &[], &[], &[], &[], 1.1, 0.0, 0.0);
+        let unrolled = HeartlineRollNode::build(&anchor_at(20.0, 15.0), &config, 0.0, false, &zero_g(), &[], &[], &[], &[], 1.1, 0.0, 0.0);
+
+        assert_eq!(rolled.len(), unrolled.len());
+        for (a, b) in rolled.iter().zip(&unrolled) {
+            assert!((a.heart_position - b.heart_position).magnitude() < 5e-2);
+        }
+        assert!(rolled[1..].iter().all(|point| point.normal_force.abs() < 1e-2));
+    }
+}
@@ -0,0 +1,339 @@
+use std::fmt;
+
+use kexedit_core::{sim, Keyframe, Point};
+
+use crate::domain::{CurveDomain, Locator, PropertyCurve, PropertyDomains};
+use crate::geometric::step_geometric;
+use crate::roll::{roll_to_angle, solve_bank, RollChannel};
+use crate::PropertyId;
+
+const MAX_ITERATIONS: usize = 1_000_000;
+
+/// Largest difference between anchor and helix pitch, in radians, that the
+/// first step may take up; about 0.3 G at 15 m/s.
+const PITCH_TOLERANCE: f32 = 0.1 * std::f32::consts::PI / 180.0;
+
+/// How a helix gains or loses height.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum HelixClimb {
+    /// Height gained over the whole arc in metres; negative descends.
+    Rise(f32),
+    /// Constant pitch in degrees; negative descends.
+    Pitch(f32),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct HelixParams {
+    /// Radius of the heartline seen from above, in metres.
+    pub radius: f32,
+    /// Degrees turned about the vertical; negative turns the other way.
+    pub arc: f32,
+    pub climb: HelixClimb,
+}
+
+impl HelixParams {
+    pub const fn new(radius: f32, arc: f32, climb: HelixClimb) -> Self {
+        Self { radius, arc, climb }
+    }
+
+    /// Pitch held throughout the helix, in radians.
+    pub fn pitch(&self) -> f32 {
+        match self.climb {
+            HelixClimb::Rise(rise) => rise.atan2(self.radius * self.arc.abs().to_radians()),
+            HelixClimb::Pitch(pitch) => pitch.to_radians(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HelixError {
+    /// Radius in metres; must be positive.
+    InvalidRadius(f32),
+    /// Helix pitch in degrees; must be strictly between -90 and 90.
+    InvalidPitch(f32),
+    /// Anchor and helix pitch in degrees, further apart than the first step can take.
+    PitchMismatch { anchor: f32, helix: f32 },
+}
+
+impl fmt::Display for HelixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidRadius(radius) => write!(f, "helix radius {} must be positive", radius),
+            Self::InvalidPitch(pitch) => write!(f, "helix pitch {}° must be within ±90°", pitch),
+            Self::PitchMismatch { anchor, helix } => {
+                write!(f, "anchor pitch {}° does not match helix pitch {}°", anchor, helix)
+            }
+        }
+    }
+}
+
+impl std::error::Error for HelixError {}
+
+/// Constant-radius helix about a vertical axis. Pitch is held throughout and
+/// the track yaws at `cos(pitch) * v / radius`. The last step is shortened so
+/// the track turns exactly `arc`.
+///
+/// The anchor must already be at the helix pitch, e.g. the end of a transition
+/// built to it; a mismatch would be taken in the first step as a normal-force
+/// spike, so it is rejected instead.
+pub struct HelixNode;
+
+impl HelixNode {
+    /// Roll and lateral force are keyed by degrees turned by default, the other
+    /// properties by time. With `auto_bank` the roll is solved each step to hold
+    /// the keyed lateral force, zero when no keys are given.
+    ///
+    /// Fails without building if the radius is not positive, the pitch is not
+    /// within ±90°, or the anchor is not at the helix pitch.
+    #[allow(clippy::too_many_arguments)]
+    pub fn build(
+        anchor: &Point,
+        params: &HelixParams,
+        driven: bool,
+        auto_bank: bool,
+        roll_channel: RollChannel,
+        roll: &[Keyframe],
+        lateral_force: &[Keyframe],
+        driven_velocity: &[Keyframe],
+        heart_offset: &[Keyframe],
+        friction: &[Keyframe],
+        resistance: &[Keyframe],
+        domains: &PropertyDomains,
+        anchor_heart: f32,
+        anchor_friction: f32,
+        anchor_resistance: f32,
+    ) -> Result<Vec<Point>, HelixError> {
+        if params.radius.is_nan() || params.radius <= 0.0 {
+            return Err(HelixError::InvalidRadius(params.radius));
+        }
+        let pitch = params.pitch();
+        if pitch.is_nan() || pitch.abs() >= std::f32::consts::FRAC_PI_2 {
+            return Err(HelixError::InvalidPitch(pitch.to_degrees()));
+        }
+        let anchor_pitch = anchor.frame().pitch();
+        if (pitch - anchor_pitch).abs() > PITCH_TOLERANCE {
+            return Err(HelixError::PitchMismatch { anchor: anchor_pitch.to_degrees(), helix: pitch.to_degrees() });
+        }
+
+        let mut driven_velocity = PropertyCurve::new(driven_velocity, domains.get(PropertyId::DrivenVelocity));
+        let mut heart_offset = PropertyCurve::new(heart_offset, domains.get(PropertyId::HeartOffset));
+        let mut friction = PropertyCurve::new(friction, domains.get(PropertyId::Friction));
+        let mut resistance = PropertyCurve::new(resistance, domains.get(PropertyId::Resistance));
+        let mut lateral_force = PropertyCurve::new(lateral_force, domains.get(PropertyId::LateralForce).resolve(CurveDomain::Angle));
+        let mut roll = PropertyCurve::new(roll, domains.get(roll_channel.property()).resolve(CurveDomain::Angle));
+        let locator = Locator::new(anchor);
+
+        let mut result = Vec::new();
+        result.push(*anchor);
+
+        let arc = params.arc.abs();
+        let direction = params.arc.signum();
+
+        let mut state = *anchor;
+        let mut angle = 0.0;
+        let mut accumulated_roll = 0.0;
+        let mut prev_heart_offset = anchor_heart;
+        let mut prev_friction = anchor_friction;
+        let mut index = 0;
+
+        while angle < arc - sim::EPSILON {
+            if index >= MAX_ITERATIONS {
+                break;
+            }
+
+            let mut prev = state;
+            let t = index as f32 / sim::HZ;
+            let mut here = locator.locate(&prev, index, t);
+            here.angle = angle;
+
+            if driven {
+                let velocity = driven_velocity.evaluate(&here, prev.velocity);
+                if velocity < sim::MIN_VELOCITY {
+                    break;
+                }
+                prev = prev.with_velocity(velocity, prev_heart_offset, prev_friction, true);
+            } else if prev.velocity < sim::MIN_VELOCITY {
+                if prev.frame().pitch() < 0.0 {
+                    prev = prev.with_velocity(sim::MIN_VELOCITY, prev_heart_offset, prev_friction, true);
+                } else {
+                    break;
+                }
+            }
+
+            let heart_offset_val = heart_offset.evaluate(&here, anchor_heart);
+            let friction_val = friction.evaluate(&here, anchor_friction);
+            let resistance_val = resistance.evaluate(&here, anchor_resistance);
+
+            let delta_pitch = pitch - prev.frame().pitch();
+            let mut step_length = prev.velocity / sim::HZ;
+            let mut yaw = pitch.cos() * step_length / params.radius;
+            let remaining = (arc - angle).to_radians();
+            if yaw > remaining {
+                step_length *= remaining / yaw;
+                yaw = remaining;
+            }
+            let delta_yaw = direction * yaw;
+
+            angle += yaw.to_degrees();
+            here.angle = angle;
+
+            let step = |delta_roll: f32, roll_speed_val: f32, accumulated_roll: &mut f32| {
+                step_geometric(
+                    &prev,
+                    heart_offset_val,
+                    friction_val,
+                    resistance_val,
+                    delta_roll,
+                    delta_pitch,
+                    delta_yaw,
+                    step_length,
+                    driven,
+                    true,
+                    roll_speed_val,
+                    accumulated_roll,
+                    state.velocity,
+                )
+            };
+
+            let roll_speed_val = if auto_bank {
+                let target = lateral_force.evaluate(&here, 0.0);
+                let (delta_roll, _) = solve_bank(target, |delta_roll| {
+                    let mut trial_roll = accumulated_roll;
+                    step(delta_roll, 0.0, &mut trial_roll)
+                });
+                delta_roll * sim::HZ
+            } else if roll_channel == RollChannel::Angle {
+                let mut trial_roll = accumulated_roll;
+                let unrolled = step(0.0, 0.0, &mut trial_roll);
+                roll_to_angle(&unrolled, roll.evaluate(&here, unrolled.roll())) * sim::HZ
+            } else {
+                roll.evaluate(&here, 0.0)
+            };
+
+            let curr = step(roll_speed_val / sim::HZ, roll_speed_val, &mut accumulated_roll);
+
+            result.push(curr);
+            state = curr;
+            prev_heart_offset = heart_offset_val;
+            prev_friction = friction_val;
+            index += 1;
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kexedit_core::{Float3, Frame};
+    use approx::assert_relative_eq;
+
+    /// 10 m up at 15 m/s, already pitched to `pitch` radians.
+    fn anchor_pitched(pitch: f32) -> Point {
+        let direction = Frame::DEFAULT.with_pitch(pitch).direction;
+        Point::create(Float3::new(0.0, 10.0, 0.0), direction, 0.0, 15.0, 1.1, 0.0, 0.0).with_velocity(15.0, 1.1, 0.0, true)
+    }
+
+    fn try_build(anchor: &Point, params: &HelixParams) -> Result<Vec<Point>, HelixError> {
+        HelixNode::build(
+            anchor, params, true, false, RollChannel::Speed, &[], &[], &[Keyframe::simple(0.0, 15.0)], &[], &[], &[],
+            &PropertyDomains::DEFAULT, 1.1, 0.0, 0.0,
+        )
+    }
+
+    fn build(params: &HelixParams, driven: bool, auto_bank: bool) -> Vec<Point> {
+        HelixNode::build(
+            &anchor_pitched(params.pitch()), params, driven, auto_bank, RollChannel::Speed, &[], &[], &[Keyframe::simple(0.0, 15.0)], &[], &[], &[],
+            &PropertyDomains::DEFAULT, 1.1, 0.0, 0.0,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn full_turn_rises_by_requested_height() {
+        let params = HelixParams::new(20.0, 360.0, HelixClimb::Rise(10.0));
+
+        let result = build(&params, true, false);
+        let first = result[0].heart_position;
+        let last = result.last().unwrap().heart_position;
+
+        assert_relative_eq!(last.y - first.y, 10.0, epsilon = 0.2);
+        assert!((last.x - first.x).hypot(last.z - first.z) < 0.5);
+        let mid = result[result.len() / 2].frame().pitch();
+        assert_relative_eq!(mid, params.pitch(), epsilon = 1e-4);
+    }
+
+    #[test]
+    fn half_turn_spans_the_diameter() {
+        let params = HelixParams::new(20.0, -180.0, HelixClimb::Pitch(-5.0));
+
+        let result = build(&params, true, false);
+        let first = result[0].heart_position;
+        let last = result.last().unwrap().heart_position;
+
+        assert_relative_eq!((last.x - first.x).hypot(last.z - first.z), 40.0, epsilon = 0.5);
+        assert!(last.y < first.y);
+        assert_relative_eq!(result.last().unwrap().direction.dot(result[1].direction), -10f32.to_radians().cos(), epsilon = 1e-3);
+    }
+
+    #[test]
+    fn turns_exactly_the_requested_arc() {
+        let params = HelixParams::new(20.0, 100.0, HelixClimb::Pitch(0.0));
+
+        let result = build(&params, true, false);
+        let turned = sim::wrap_angle(result.last().unwrap().frame().yaw() - result[0].frame().yaw());
+
+        assert_relative_eq!(turned.abs(), 100f32.to_radians(), epsilon = 1e-4);
+        assert_relative_eq!(result.last().unwrap().heart_arc, 20.0 * 100f32.to_radians(), epsilon = 1e-2);
+    }
+
+    #[test]
+    fn auto_bank_holds_zero_lateral_force() {
+        let params = HelixParams::new(20.0, 270.0, HelixClimb::Rise(8.0));
+
+        let result = build(&params, true, true);
+
+        for point in &result[5..] {
+            assert!(point.lateral_force.abs() < 1e-2, "lateral force {}", point.lateral_force);
+        }
+        assert!(result.last().unwrap().roll().abs() > 0.5);
+    }
+
+    #[test]
+    fn unpowered_climb_loses_speed() {
+        let params = HelixParams::new(20.0, 180.0, HelixClimb::Rise(5.0));
+
+        let result = build(&params, false, false);
+
+        assert!(result.last().unwrap().velocity < result[0].velocity - 2.0);
+    }
+
+    #[test]
+    fn holds_pitch_from_the_first_step() {
+        let params = HelixParams::new(20.0, 90.0, HelixClimb::Pitch(12.0));
+
+        let result = build(&params, true, false);
+
+        for point in &result[1..] {
+            assert_relative_eq!(point.frame().pitch(), 12f32.to_radians(), epsilon = 1e-4);
+            assert!(point.normal_force > 0.9 && point.normal_force < 1.1, "normal force {}", point.normal_force);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_params_and_mismatched_anchor() {
+        let level = anchor_pitched(0.0);
+
+        let flat = |radius| HelixParams::new(radius, 90.0, HelixClimb::Pitch(0.0));
+        assert_eq!(try_build(&level, &flat(0.0)), Err(HelixError::InvalidRadius(0.0)));
+        assert_eq!(try_build(&level, &flat(-5.0)), Err(HelixError::InvalidRadius(-5.0)));
+
+        let steep = HelixParams::new(20.0, 90.0, HelixClimb::Pitch(-90.0));
+        assert!(matches!(try_build(&level, &steep), Err(HelixError::InvalidPitch(_))));
+
+        let climbing = HelixParams::new(20.0, 90.0, HelixClimb::Pitch(10.0));
+        assert!(matches!(try_build(&level, &climbing), Err(HelixError::PitchMismatch { .. })));
+        assert!(try_build(&anchor_pitched(climbing.pitch()), &climbing).is_ok());
+    }
+}
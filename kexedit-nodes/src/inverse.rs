@@ -0,0 +1,324 @@
+use kexedit_core::{fit_keyframes, sim, Float3, PhysicsParams, Point};
+
+use crate::force::advance;
+use crate::solver::ForceSection;
+use crate::{DurationType, IterationConfig};
+
+const FORCE_ITERATIONS: usize = 6;
+const FORCE_PROBE: f32 = 1e-2;
+/// Fraction of the offset from the path steered out per step.
+const CORRECTION_GAIN: f32 = 0.1;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct InverseConfig {
+    /// Largest allowed distance between the rebuilt heart line and the input path, in metres.
+    pub tolerance: f32,
+    /// Starting keyframe tolerance for normal and lateral force, in G.
+    pub force_tolerance: f32,
+    /// Starting keyframe tolerance for roll speed, in radians per second.
+    pub roll_tolerance: f32,
+    /// How many times the keyframe tolerances may be halved to meet `tolerance`.
+    pub max_refinements: usize,
+}
+
+impl InverseConfig {
+    pub const DEFAULT: Self = Self::new(0.05, 0.05, 0.05, 10);
+
+    pub const fn new(tolerance: f32, force_tolerance: f32, roll_tolerance: f32, max_refinements: usize) -> Self {
+        Self {
+            tolerance,
+            force_tolerance,
+            roll_tolerance,
+            max_refinements,
+        }
+    }
+}
+
+impl Default for InverseConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InverseFit {
+    /// Time-based section whose roll speed, normal and lateral force keyframes reproduce the path.
+    pub section: ForceSection,
+    /// `section.build()`.
+    pub points: Vec<Point>,
+    /// Largest distance from a rebuilt point to the input path.
+    pub max_error: f32,
+    pub converged: bool,
+}
+
+/// Per-step inputs that make `force::advance` follow the path, at `t = i / sim::HZ`.
+#[derive(Debug, Clone, Default)]
+struct Samples {
+    times: Vec<f32>,
+    roll_speed: Vec<f32>,
+    normal_force: Vec<f32>,
+    lateral_force: Vec<f32>,
+}
+
+/// Polyline through the heart positions of a path.
+struct Polyline<'a> {
+    points: &'a [Point],
+}
+
+impl Polyline<'_> {
+    /// Closest point on the polyline to `position`, with the path's direction
+    /// and lateral axis there, searching forward from segment `*cursor`.
+    fn closest(&self, position: Float3, cursor: &mut usize) -> (Float3, Float3, Float3) {
+        let project = |i: usize| {
+            let (a, b) = (self.points[i].heart_position, self.points[i + 1].heart_position);
+            let ab = b - a;
+            let length_sq = ab.dot(ab);
+            let s = if length_sq < sim::EPSILON {
+                0.0
+            } else {
+                ((position - a).dot(ab) / length_sq).clamp(0.0, 1.0)
+            };
+            (a + ab * s, s)
+        };
+
+        let (mut best, mut s) = project(*cursor);
+        while *cursor + 2 < self.points.len() {
+            let (next, next_s) = project(*cursor + 1);
+            if (next - position).magnitude() > (best - position).magnitude() {
+                break;
+            }
+            (best, s) = (next, next_s);
+            *cursor += 1;
+        }
+
+        let (a, b) = (&self.points[*cursor], &self.points[*cursor + 1]);
+        let direction = (a.direction * (1.0 - s) + b.direction * s).normalize();
+        let lateral = (a.lateral * (1.0 - s) + b.lateral * s).normalize();
+        (best, direction, lateral)
+    }
+
+    fn distance(&self, position: Float3, cursor: &mut usize) -> f32 {
+        (self.closest(position, cursor).0 - position).magnitude()
+    }
+}
+
+/// Normal and lateral force that turn `prev` to `target_direction` in one step.
+fn solve_forces(prev: &Point, target_direction: Float3, physics: &PhysicsParams, guess: (f32, f32)) -> (f32, f32) {
+    let frame = prev.frame();
+    let error = |normal_force: f32, lateral_force: f32| {
+        let miss = advance(prev, normal_force, lateral_force, physics, 0.0, prev.velocity).direction - target_direction;
+        (miss.dot(frame.normal), miss.dot(frame.lateral))
+    };
+
+    let (mut normal_force, mut lateral_force) = guess;
+    for _ in 0..FORCE_ITERATIONS {
+        let (en, el) = error(normal_force, lateral_force);
+        if en.abs() < 1e-7 && el.abs() < 1e-7 {
+            break;
+        }
+        let (nn, nl) = error(normal_force + FORCE_PROBE, lateral_force);
+        let (ln, ll) = error(normal_force, lateral_force + FORCE_PROBE);
+        let (a, b) = ((nn - en) / FORCE_PROBE, (ln - en) / FORCE_PROBE);
+        let (c, d) = ((nl - el) / FORCE_PROBE, (ll - el) / FORCE_PROBE);
+        let det = a * d - b * c;
+        if det.abs() < f32::MIN_POSITIVE {
+            break;
+        }
+        normal_force -= (d * en - b * el) / det;
+        lateral_force -= (a * el - c * en) / det;
+    }
+    (normal_force, lateral_force)
+}
+
+/// Steps through the path the way `force::build` would, solving each step's
+/// forces so the heart line stays on the path and its roll so the lateral
+/// axis matches the path's.
+fn derive_samples(anchor: &Point, path: &Polyline) -> Samples {
+    let mut samples = Samples::default();
+    let physics = |delta_roll: f32| {
+        PhysicsParams::new(anchor.heart_offset, anchor.friction, anchor.resistance, delta_roll, false)
+    };
+    let last = &path.points[path.points.len() - 1];
+
+    let mut state = *anchor;
+    let mut cursor = 0;
+    let mut forces = (1.0, 0.0);
+    while state.velocity >= sim::MIN_VELOCITY {
+        let remaining = (last.heart_position - state.heart_position).dot(last.direction);
+        if cursor + 2 >= path.points.len() && remaining < state.velocity / sim::HZ {
+            break;
+        }
+
+        let predicted = advance(&state, forces.0, forces.1, &physics(0.0), 0.0, state.velocity);
+        let (on_path, direction, _) = path.closest(predicted.heart_position, &mut cursor.clone());
+        let step = state.velocity / sim::HZ;
+        let correction = (on_path - predicted.heart_position) * (CORRECTION_GAIN / step);
+        forces = solve_forces(&state, (direction + correction).normalize(), &physics(0.0), forces);
+
+        let unrolled = advance(&state, forces.0, forces.1, &physics(0.0), 0.0, state.velocity);
+        let (_, _, lateral) = path.closest(unrolled.heart_position, &mut cursor);
+        let lateral = (lateral - unrolled.direction * lateral.dot(unrolled.direction)).normalize();
+        let delta_roll = -unrolled
+            .direction
+            .dot(unrolled.lateral.cross(lateral))
+            .atan2(unrolled.lateral.dot(lateral));
+        let roll_speed = delta_roll * sim::HZ;
+
+        let curr = advance(&state, forces.0, forces.1, &physics(delta_roll), roll_speed, state.velocity);
+        state = curr;
+
+        samples.times.push((samples.times.len() + 1) as f32 / sim::HZ);
+        samples.roll_speed.push(roll_speed);
+        samples.normal_force.push(forces.0);
+        samples.lateral_force.push(forces.1);
+    }
+    samples
+}
+
+fn max_error(points: &[Point], path: &Polyline) -> f32 {
+    let mut cursor = 0;
+    points
+        .iter()
+        .fold(0.0f32, |acc, p| acc.max(path.distance(p.heart_position, &mut cursor)))
+}
+
+/// Fits roll speed, normal and lateral force keyframes so that `force::build`
+/// from `anchor` reproduces the heart line and lateral axis of `path`.
+///
+/// `path` is any sequence of points with heart positions and frames, such as
+/// the output of `CopyPathNode` or an imported spline; only its geometry is
+/// used, while velocity, heart offset, friction and resistance come from
+/// `anchor`, which should sit at `path[0]`. The section is unpowered, so the
+/// path must be traversable on the anchor's energy. Keyframes start coarse and
+/// are refined until the rebuilt heart line stays within `config.tolerance`
+/// of the path, so the result has as few keys as that tolerance allows.
+pub fn fit(anchor: &Point, path: &[Point], config: &InverseConfig) -> Option<InverseFit> {
+    if path.len() < 2 {
+        return None;
+    }
+
+    let polyline = Polyline { points: path };
+    let samples = derive_samples(anchor, &polyline);
+    if samples.times.is_empty() {
+        return None;
+    }
+
+    let duration = (samples.times.len() as f32 + 1.5) / sim::HZ;
+    let mut force_tolerance = config.force_tolerance;
+    let mut roll_tolerance = config.roll_tolerance;
+    let mut best: Option<InverseFit> = None;
+
+    for _ in 0..=config.max_refinements {
+        let mut section = ForceSection::new(*anchor, IterationConfig::new(duration, DurationType::Time));
+        section.roll = fit_keyframes(&samples.times, &samples.roll_speed, roll_tolerance);
+        section.normal_force = fit_keyframes(&samples.times, &samples.normal_force, force_tolerance);
+        section.lateral_force = fit_keyframes(&samples.times, &samples.lateral_force, force_tolerance);
+
+        let points = section.build();
+        let max_error = max_error(&points, &polyline);
+        let converged = max_error <= config.tolerance;
+        let fit = InverseFit {
+            section,
+            points,
+            max_error,
+            converged,
+        };
+
+        if converged {
+            return Some(fit);
+        }
+        if best.as_ref().is_none_or(|b| fit.max_error < b.max_error) {
+            best = Some(fit);
+        }
+        force_tolerance *= 0.5;
+        roll_tolerance *= 0.5;
+    }
+
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::anchor_at;
+    use crate::force;
+    use crate::roll::RollChannel;
+    use kexedit_core::Keyframe;
+
+    fn reference() -> Vec<Point> {
+        let config = IterationConfig::new(3.0, DurationType::Time);
+        let normal_force = [
+            Keyframe::simple(0.0, 1.0),
+            Keyframe::simple(1.5, 2.2),
+            Keyframe::simple(3.0, 0.8),
+        ];
+        let lateral_force = [Keyframe::simple(0.0, 0.0), Keyframe::simple(3.0, 0.3)];
+        let roll_speed = [Keyframe::simple(0.5, 0.0), Keyframe::simple(1.5, 0.8), Keyframe::simple(2.5, 0.0)];
+        force::build(
+            &anchor_at(20.0, 20.0),
+            &config,
+            false,
+            RollChannel::Speed,
+            &roll_speed,
+            &normal_force,
+            &lateral_force,
+            &[],
+            &[],
+            &[],
+            &[],
+            1.1,
+            0.0,
+            0.0,
+        )
+    }
+
+    fn key_count(section: &ForceSection) -> usize {
+        section.roll.len() + section.normal_force.len() + section.lateral_force.len()
+    }
+
+    #[test]
+    fn reproduces_force_section() {
+        let path = reference();
+
+        let fit = fit(&anchor_at(20.0, 20.0), &path, &InverseConfig::DEFAULT).unwrap();
+
+        assert!(fit.converged, "max error {}", fit.max_error);
+        assert!(fit.max_error <= InverseConfig::DEFAULT.tolerance);
+        assert!(key_count(&fit.section) < path.len() / 4);
+        let end = fit.points.last().unwrap();
+        let roll = path.last().unwrap().roll();
+        assert!((end.roll() - roll).abs() < 2e-2, "roll {} vs {}", end.roll(), roll);
+    }
+
+    #[test]
+    fn fits_geometric_section() {
+        let config = IterationConfig::new(3.0, DurationType::Time);
+        let pitch_speed = [Keyframe::simple(0.0, 0.0), Keyframe::simple(1.5, 0.4), Keyframe::simple(3.0, 0.0)];
+        let yaw_speed = [Keyframe::simple(0.0, 0.3)];
+        let path = crate::geometric::build(
+            &anchor_at(20.0, 20.0), &config, false, false, false, RollChannel::Speed, &[], &pitch_speed, &yaw_speed, &[], &[], &[],
+            &[], &[], 1.1, 0.0, 0.0,
+        );
+
+        let fit = fit(&anchor_at(20.0, 20.0), &path, &InverseConfig::DEFAULT).unwrap();
+
+        assert!(fit.converged, "max error {}", fit.max_error);
+        assert!(key_count(&fit.section) < path.len() / 4);
+    }
+
+    #[test]
+    fn looser_tolerance_needs_fewer_keys() {
+        let path = reference();
+
+        let tight = fit(&anchor_at(20.0, 20.0), &path, &InverseConfig::new(0.02, 0.05, 0.05, 10)).unwrap();
+        let loose = fit(&anchor_at(20.0, 20.0), &path, &InverseConfig::new(0.5, 0.05, 0.05, 10)).unwrap();
+
+        assert!(tight.converged && loose.converged);
+        assert!(key_count(&loose.section) <= key_count(&tight.section));
+    }
+
+    #[test]
+    fn short_path_has_no_fit() {
+        assert!(fit(&anchor_at(20.0, 20.0), &[anchor_at(20.0, 20.0)], &InverseConfig::DEFAULT).is_none());
+    }
+}
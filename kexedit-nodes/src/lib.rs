@@ -1,3 +1,6 @@
+use domain::PropertyDomains;
+use end_condition::EndCondition;
+
 #[repr(u8)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum PortId {
@@ -15,6 +18,19 @@ pub enum PortId {
     End = 11,
     Position = 12,
     Rotation = 13,
+    TargetVelocity = 14,
+    Deceleration = 15,
+    Length = 16,
+    StartPitchCurvature = 17,
+    StartYawCurvature = 18,
+    EndPitchCurvature = 19,
+    EndYawCurvature = 20,
+    Rise = 21,
+    Pitch = 22,
+    MaxNormalForce = 23,
+    TopNormalForce = 24,
+    RollAngle = 25,
+    Bank = 26,
 }
 
 #[repr(u8)]
@@ -30,6 +46,11 @@ pub enum PropertyId {
     Friction = 7,
     Resistance = 8,
     TrackStyle = 9,
+    Roll = 10,
+}
+
+impl PropertyId {
+    const COUNT: usize = 11;
 }
 
 #[repr(u8)]
@@ -43,10 +64,16 @@ pub enum NodeType {
     Anchor = 5,
     Reverse = 6,
     ReversePath = 7,
+    Brake = 8,
+    Clothoid = 9,
+    Helix = 10,
+    Loop = 11,
+    HeartlineRoll = 12,
+    Straight = 13,
 }
 
 impl NodeType {
-    const COUNT: usize = 8;
+    const COUNT: usize = 14;
 
     const fn as_index(self) -> usize {
         self as usize
@@ -60,10 +87,13 @@ pub enum DurationType {
     Distance = 1,
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct IterationConfig {
     pub duration: f32,
     pub duration_type: DurationType,
+    pub domains: PropertyDomains,
+    /// Ends the section early once met; `duration` remains the limit.
+    pub end_condition: Option<EndCondition>,
 }
 
 impl IterationConfig {
@@ -71,6 +101,19 @@ impl IterationConfig {
         Self {
             duration,
             duration_type,
+            domains: PropertyDomains::DEFAULT,
+            end_condition: None,
+        }
+    }
+
+    pub const fn with_domains(self, domains: PropertyDomains) -> Self {
+        Self { domains, ..self }
+    }
+
+    pub const fn with_end_condition(self, end_condition: EndCondition) -> Self {
+        Self {
+            end_condition: Some(end_condition),
+            ..self
         }
     }
 }
@@ -78,7 +121,7 @@ impl IterationConfig {
 const INVALID_PORT: u8 = 255;
 const INVALID_PROPERTY: u8 = 255;
 
-const INPUT_COUNTS: [usize; NodeType::COUNT] = [2, 2, 6, 4, 3, 2, 1, 1];
+const INPUT_COUNTS: [usize; NodeType::COUNT] = [2, 2, 6, 4, 3, 2, 1, 1, 4, 6, 5, 4, 3, 5];
 
 const INPUT_PORTS: [[u8; 6]; NodeType::COUNT] = [
     [PortId::Anchor as u8, PortId::Duration as u8, INVALID_PORT, INVALID_PORT, INVALID_PORT, INVALID_PORT], // Force
@@ -89,9 +132,15 @@ const INPUT_PORTS: [[u8; 6]; NodeType::COUNT] = [
     [PortId::Position as u8, PortId::Rotation as u8, INVALID_PORT, INVALID_PORT, INVALID_PORT, INVALID_PORT], // Anchor
     [PortId::Anchor as u8, INVALID_PORT, INVALID_PORT, INVALID_PORT, INVALID_PORT, INVALID_PORT], // Reverse
     [PortId::Path as u8, INVALID_PORT, INVALID_PORT, INVALID_PORT, INVALID_PORT, INVALID_PORT], // ReversePath
+    [PortId::Anchor as u8, PortId::Duration as u8, PortId::TargetVelocity as u8, PortId::Deceleration as u8, INVALID_PORT, INVALID_PORT], // Brake
+    [PortId::Anchor as u8, PortId::Length as u8, PortId::StartPitchCurvature as u8, PortId::StartYawCurvature as u8, PortId::EndPitchCurvature as u8, PortId::EndYawCurvature as u8], // Clothoid
+    [PortId::Anchor as u8, PortId::Radius as u8, PortId::Arc as u8, PortId::Rise as u8, PortId::Pitch as u8, INVALID_PORT], // Helix
+    [PortId::Anchor as u8, PortId::MaxNormalForce as u8, PortId::TopNormalForce as u8, PortId::Radius as u8, INVALID_PORT, INVALID_PORT], // Loop
+    [PortId::Anchor as u8, PortId::Duration as u8, PortId::RollAngle as u8, INVALID_PORT, INVALID_PORT, INVALID_PORT], // HeartlineRoll
+    [PortId::Anchor as u8, PortId::Length as u8, PortId::Pitch as u8, PortId::Bank as u8, PortId::TargetVelocity as u8, INVALID_PORT], // Straight
 ];
 
-const OUTPUT_COUNTS: [usize; NodeType::COUNT] = [2, 2, 2, 2, 2, 1, 1, 1];
+const OUTPUT_COUNTS: [usize; NodeType::COUNT] = [2, 2, 2, 2, 2, 1, 1, 1, 2, 2, 2, 2, 2, 2];
 
 const OUTPUT_PORTS: [[u8; 2]; NodeType::COUNT] = [
     [PortId::Anchor as u8, PortId::Path as u8], // Force
@@ -102,19 +151,31 @@ const OUTPUT_PORTS: [[u8; 2]; NodeType::COUNT] = [
     [PortId::Anchor as u8, INVALID_PORT],        // Anchor
     [PortId::Anchor as u8, INVALID_PORT],        // Reverse
     [PortId::Path as u8, INVALID_PORT],          // ReversePath
+    [PortId::Anchor as u8, PortId::Path as u8], // Brake
+    [PortId::Anchor as u8, PortId::Path as u8], // Clothoid
+    [PortId::Anchor as u8, PortId::Path as u8], // Helix
+    [PortId::Anchor as u8, PortId::Path as u8], // Loop
+    [PortId::Anchor as u8, PortId::Path as u8], // HeartlineRoll
+    [PortId::Anchor as u8, PortId::Path as u8], // Straight
 ];
 
-const PROPERTY_COUNTS: [usize; NodeType::COUNT] = [7, 7, 5, 4, 5, 0, 0, 0];
-
-const PROPERTIES: [[u8; 7]; NodeType::COUNT] = [
-    [PropertyId::RollSpeed as u8, PropertyId::NormalForce as u8, PropertyId::LateralForce as u8, PropertyId::DrivenVelocity as u8, PropertyId::HeartOffset as u8, PropertyId::Friction as u8, PropertyId::Resistance as u8], // Force
-    [PropertyId::RollSpeed as u8, PropertyId::PitchSpeed as u8, PropertyId::YawSpeed as u8, PropertyId::DrivenVelocity as u8, PropertyId::HeartOffset as u8, PropertyId::Friction as u8, PropertyId::Resistance as u8], // Geometric
-    [PropertyId::RollSpeed as u8, PropertyId::DrivenVelocity as u8, PropertyId::HeartOffset as u8, PropertyId::Friction as u8, PropertyId::Resistance as u8, INVALID_PROPERTY, INVALID_PROPERTY], // Curved
-    [PropertyId::DrivenVelocity as u8, PropertyId::HeartOffset as u8, PropertyId::Friction as u8, PropertyId::Resistance as u8, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY], // CopyPath
-    [PropertyId::DrivenVelocity as u8, PropertyId::HeartOffset as u8, PropertyId::Friction as u8, PropertyId::Resistance as u8, PropertyId::TrackStyle as u8, INVALID_PROPERTY, INVALID_PROPERTY], // Bridge
-    [INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY], // Anchor
-    [INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY], // Reverse
-    [INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY], // ReversePath
+const PROPERTY_COUNTS: [usize; NodeType::COUNT] = [8, 9, 7, 4, 5, 0, 0, 0, 3, 6, 7, 3, 5, 0];
+
+const PROPERTIES: [[u8; 9]; NodeType::COUNT] = [
+    [PropertyId::RollSpeed as u8, PropertyId::NormalForce as u8, PropertyId::LateralForce as u8, PropertyId::DrivenVelocity as u8, PropertyId::HeartOffset as u8, PropertyId::Friction as u8, PropertyId::Resistance as u8, PropertyId::Roll as u8, INVALID_PROPERTY], // Force
+    [PropertyId::RollSpeed as u8, PropertyId::PitchSpeed as u8, PropertyId::YawSpeed as u8, PropertyId::DrivenVelocity as u8, PropertyId::HeartOffset as u8, PropertyId::Friction as u8, PropertyId::Resistance as u8, PropertyId::LateralForce as u8, PropertyId::Roll as u8], // Geometric
+    [PropertyId::RollSpeed as u8, PropertyId::DrivenVelocity as u8, PropertyId::HeartOffset as u8, PropertyId::Friction as u8, PropertyId::Resistance as u8, PropertyId::LateralForce as u8, PropertyId::Roll as u8, INVALID_PROPERTY, INVALID_PROPERTY], // Curved
+    [PropertyId::DrivenVelocity as u8, PropertyId::HeartOffset as u8, PropertyId::Friction as u8, PropertyId::Resistance as u8, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY], // CopyPath
+    [PropertyId::DrivenVelocity as u8, PropertyId::HeartOffset as u8, PropertyId::Friction as u8, PropertyId::Resistance as u8, PropertyId::TrackStyle as u8, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY], // Bridge
+    [INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY], // Anchor
+    [INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY], // Reverse
+    [INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY], // ReversePath
+    [PropertyId::HeartOffset as u8, PropertyId::Friction as u8, PropertyId::Resistance as u8, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY], // Brake
+    [PropertyId::RollSpeed as u8, PropertyId::DrivenVelocity as u8, PropertyId::HeartOffset as u8, PropertyId::Friction as u8, PropertyId::Resistance as u8, PropertyId::Roll as u8, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY], // Clothoid
+    [PropertyId::RollSpeed as u8, PropertyId::DrivenVelocity as u8, PropertyId::HeartOffset as u8, PropertyId::Friction as u8, PropertyId::Resistance as u8, PropertyId::LateralForce as u8, PropertyId::Roll as u8, INVALID_PROPERTY, INVALID_PROPERTY], // Helix
+    [PropertyId::HeartOffset as u8, PropertyId::Friction as u8, PropertyId::Resistance as u8, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY], // Loop
+    [PropertyId::NormalForce as u8, PropertyId::DrivenVelocity as u8, PropertyId::HeartOffset as u8, PropertyId::Friction as u8, PropertyId::Resistance as u8, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY], // HeartlineRoll
+    [INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY, INVALID_PROPERTY], // Straight
 ];
 
 pub struct NodeSchema;
@@ -157,7 +218,7 @@ impl NodeSchema {
     }
 
     pub const fn property(node_type: NodeType, index: usize) -> Option<PropertyId> {
-        if index >= 7 {
+        if index >= 9 {
             return None;
         }
         let property_u8 = PROPERTIES[node_type.as_index()][index];
@@ -203,6 +264,18 @@ pub mod reverse_path;
 pub mod curved;
 pub mod bridge;
 pub mod copy_path;
+pub mod brake;
+pub mod analysis;
+pub mod roll;
+pub mod solver;
+pub mod inverse;
+pub mod domain;
+pub mod end_condition;
+pub mod clothoid;
+pub mod helix;
+pub mod vertical_loop;
+pub mod heartline_roll;
+pub mod straight;
 
 #[cfg(test)]
 mod golden;
@@ -210,6 +283,9 @@ mod golden;
 #[cfg(test)]
 mod point_comparer;
 
+#[cfg(test)]
+mod fixtures;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -223,7 +299,12 @@ mod tests {
             (PropertyId::PitchSpeed, NodeType::Geometric, 1),
             (PropertyId::YawSpeed, NodeType::Geometric, 2),
             (PropertyId::DrivenVelocity, NodeType::Curved, 1),
+            (PropertyId::LateralForce, NodeType::Geometric, 7),
+            (PropertyId::LateralForce, NodeType::Curved, 5),
             (PropertyId::TrackStyle, NodeType::Bridge, 4),
+            (PropertyId::Roll, NodeType::Force, 7),
+            (PropertyId::Roll, NodeType::Geometric, 8),
+            (PropertyId::Roll, NodeType::Curved, 6),
         ];
 
         for (property, node, expected_index) in test_cases {
@@ -253,6 +334,12 @@ mod tests {
         assert_eq!(NodeSchema::input_count(NodeType::Anchor), 2);
         assert_eq!(NodeSchema::input_count(NodeType::Reverse), 1);
         assert_eq!(NodeSchema::input_count(NodeType::ReversePath), 1);
+        assert_eq!(NodeSchema::input_count(NodeType::Brake), 4);
+        assert_eq!(NodeSchema::input_count(NodeType::Clothoid), 6);
+        assert_eq!(NodeSchema::input_count(NodeType::Helix), 5);
+        assert_eq!(NodeSchema::input_count(NodeType::Loop), 4);
+        assert_eq!(NodeSchema::input_count(NodeType::HeartlineRoll), 3);
+        assert_eq!(NodeSchema::input_count(NodeType::Straight), 5);
     }
 
     #[test]
@@ -265,18 +352,30 @@ mod tests {
         assert_eq!(NodeSchema::output_count(NodeType::Anchor), 1);
         assert_eq!(NodeSchema::output_count(NodeType::Reverse), 1);
         assert_eq!(NodeSchema::output_count(NodeType::ReversePath), 1);
+        assert_eq!(NodeSchema::output_count(NodeType::Brake), 2);
+        assert_eq!(NodeSchema::output_count(NodeType::Clothoid), 2);
+        assert_eq!(NodeSchema::output_count(NodeType::Helix), 2);
+        assert_eq!(NodeSchema::output_count(NodeType::Loop), 2);
+        assert_eq!(NodeSchema::output_count(NodeType::HeartlineRoll), 2);
+        assert_eq!(NodeSchema::output_count(NodeType::Straight), 2);
     }
 
     #[test]
     fn node_schema_property_counts() {
-        assert_eq!(NodeSchema::property_count(NodeType::Force), 7);
-        assert_eq!(NodeSchema::property_count(NodeType::Geometric), 7);
-        assert_eq!(NodeSchema::property_count(NodeType::Curved), 5);
+        assert_eq!(NodeSchema::property_count(NodeType::Force), 8);
+        assert_eq!(NodeSchema::property_count(NodeType::Geometric), 9);
+        assert_eq!(NodeSchema::property_count(NodeType::Curved), 7);
         assert_eq!(NodeSchema::property_count(NodeType::CopyPath), 4);
         assert_eq!(NodeSchema::property_count(NodeType::Bridge), 5);
         assert_eq!(NodeSchema::property_count(NodeType::Anchor), 0);
         assert_eq!(NodeSchema::property_count(NodeType::Reverse), 0);
         assert_eq!(NodeSchema::property_count(NodeType::ReversePath), 0);
+        assert_eq!(NodeSchema::property_count(NodeType::Brake), 3);
+        assert_eq!(NodeSchema::property_count(NodeType::Clothoid), 6);
+        assert_eq!(NodeSchema::property_count(NodeType::Helix), 7);
+        assert_eq!(NodeSchema::property_count(NodeType::Loop), 3);
+        assert_eq!(NodeSchema::property_count(NodeType::HeartlineRoll), 5);
+        assert_eq!(NodeSchema::property_count(NodeType::Straight), 0);
     }
 
     #[test]
@@ -297,6 +396,63 @@ mod tests {
         assert_eq!(NodeSchema::input(NodeType::Curved, 6), None);
     }
 
+    #[test]
+    fn node_schema_brake_inputs() {
+        assert_eq!(NodeSchema::input(NodeType::Brake, 0), Some(PortId::Anchor));
+        assert_eq!(NodeSchema::input(NodeType::Brake, 1), Some(PortId::Duration));
+        assert_eq!(NodeSchema::input(NodeType::Brake, 2), Some(PortId::TargetVelocity));
+        assert_eq!(NodeSchema::input(NodeType::Brake, 3), Some(PortId::Deceleration));
+        assert_eq!(NodeSchema::input(NodeType::Brake, 4), None);
+    }
+
+    #[test]
+    fn node_schema_clothoid_inputs() {
+        assert_eq!(NodeSchema::input(NodeType::Clothoid, 0), Some(PortId::Anchor));
+        assert_eq!(NodeSchema::input(NodeType::Clothoid, 1), Some(PortId::Length));
+        assert_eq!(NodeSchema::input(NodeType::Clothoid, 2), Some(PortId::StartPitchCurvature));
+        assert_eq!(NodeSchema::input(NodeType::Clothoid, 3), Some(PortId::StartYawCurvature));
+        assert_eq!(NodeSchema::input(NodeType::Clothoid, 4), Some(PortId::EndPitchCurvature));
+        assert_eq!(NodeSchema::input(NodeType::Clothoid, 5), Some(PortId::EndYawCurvature));
+        assert_eq!(NodeSchema::input(NodeType::Clothoid, 6), None);
+    }
+
+    #[test]
+    fn node_schema_helix_inputs() {
+        assert_eq!(NodeSchema::input(NodeType::Helix, 0), Some(PortId::Anchor));
+        assert_eq!(NodeSchema::input(NodeType::Helix, 1), Some(PortId::Radius));
+        assert_eq!(NodeSchema::input(NodeType::Helix, 2), Some(PortId::Arc));
+        assert_eq!(NodeSchema::input(NodeType::Helix, 3), Some(PortId::Rise));
+        assert_eq!(NodeSchema::input(NodeType::Helix, 4), Some(PortId::Pitch));
+        assert_eq!(NodeSchema::input(NodeType::Helix, 5), None);
+    }
+
+    #[test]
+    fn node_schema_loop_inputs() {
+        assert_eq!(NodeSchema::input(NodeType::Loop, 0), Some(PortId::Anchor));
+        assert_eq!(NodeSchema::input(NodeType::Loop, 1), Some(PortId::MaxNormalForce));
+        assert_eq!(NodeSchema::input(NodeType::Loop, 2), Some(PortId::TopNormalForce));
+        assert_eq!(NodeSchema::input(NodeType::Loop, 3), Some(PortId::Radius));
+        assert_eq!(NodeSchema::input(NodeType::Loop, 4), None);
+    }
+
+    #[test]
+    fn node_schema_heartline_roll_inputs() {
+        assert_eq!(NodeSchema::input(NodeType::HeartlineRoll, 0), Some(PortId::Anchor));
+        assert_eq!(NodeSchema::input(NodeType::HeartlineRoll, 1), Some(PortId::Duration));
+        assert_eq!(NodeSchema::input(NodeType::HeartlineRoll, 2), Some(PortId::RollAngle));
+        assert_eq!(NodeSchema::input(NodeType::HeartlineRoll, 3), None);
+    }
+
+    #[test]
+    fn node_schema_straight_inputs() {
+        assert_eq!(NodeSchema::input(NodeType::Straight, 0), Some(PortId::Anchor));
+        assert_eq!(NodeSchema::input(NodeType::Straight, 1), Some(PortId::Length));
+        assert_eq!(NodeSchema::input(NodeType::Straight, 2), Some(PortId::Pitch));
+        assert_eq!(NodeSchema::input(NodeType::Straight, 3), Some(PortId::Bank));
+        assert_eq!(NodeSchema::input(NodeType::Straight, 4), Some(PortId::TargetVelocity));
+        assert_eq!(NodeSchema::input(NodeType::Straight, 5), None);
+    }
+
     #[test]
     fn node_schema_outputs() {
         assert_eq!(NodeSchema::output(NodeType::Force, 0), Some(PortId::Anchor));
@@ -314,7 +470,8 @@ mod tests {
         assert_eq!(NodeSchema::property(NodeType::Force, 4), Some(PropertyId::HeartOffset));
         assert_eq!(NodeSchema::property(NodeType::Force, 5), Some(PropertyId::Friction));
         assert_eq!(NodeSchema::property(NodeType::Force, 6), Some(PropertyId::Resistance));
-        assert_eq!(NodeSchema::property(NodeType::Force, 7), None);
+        assert_eq!(NodeSchema::property(NodeType::Force, 7), Some(PropertyId::Roll));
+        assert_eq!(NodeSchema::property(NodeType::Force, 8), None);
     }
 
     #[test]
@@ -351,6 +508,12 @@ mod tests {
             NodeType::Anchor,
             NodeType::Reverse,
             NodeType::ReversePath,
+            NodeType::Brake,
+            NodeType::Clothoid,
+            NodeType::Helix,
+            NodeType::Loop,
+            NodeType::HeartlineRoll,
+            NodeType::Straight,
         ] {
             let input_count = NodeSchema::input_count(node_type);
             for i in 0..input_count {
@@ -398,6 +561,7 @@ mod tests {
                 PropertyId::HeartOffset,
                 PropertyId::Friction,
                 PropertyId::Resistance,
+                PropertyId::Roll,
             ]),
             (NodeType::Geometric, vec![
                 PropertyId::RollSpeed,
@@ -407,6 +571,7 @@ mod tests {
                 PropertyId::HeartOffset,
                 PropertyId::Friction,
                 PropertyId::Resistance,
+                PropertyId::Roll,
             ]),
             (NodeType::Curved, vec![
                 PropertyId::RollSpeed,
@@ -414,6 +579,7 @@ mod tests {
                 PropertyId::HeartOffset,
                 PropertyId::Friction,
                 PropertyId::Resistance,
+                PropertyId::Roll,
             ]),
             (NodeType::CopyPath, vec![
                 PropertyId::DrivenVelocity,
@@ -428,6 +594,40 @@ mod tests {
                 PropertyId::Resistance,
                 PropertyId::TrackStyle,
             ]),
+            (NodeType::Brake, vec![
+                PropertyId::HeartOffset,
+                PropertyId::Friction,
+                PropertyId::Resistance,
+            ]),
+            (NodeType::Clothoid, vec![
+                PropertyId::RollSpeed,
+                PropertyId::DrivenVelocity,
+                PropertyId::HeartOffset,
+                PropertyId::Friction,
+                PropertyId::Resistance,
+                PropertyId::Roll,
+            ]),
+            (NodeType::Helix, vec![
+                PropertyId::RollSpeed,
+                PropertyId::DrivenVelocity,
+                PropertyId::HeartOffset,
+                PropertyId::Friction,
+                PropertyId::Resistance,
+                PropertyId::LateralForce,
+                PropertyId::Roll,
+            ]),
+            (NodeType::Loop, vec![
+                PropertyId::HeartOffset,
+                PropertyId::Friction,
+                PropertyId::Resistance,
+            ]),
+            (NodeType::HeartlineRoll, vec![
+                PropertyId::NormalForce,
+                PropertyId::DrivenVelocity,
+                PropertyId::HeartOffset,
+                PropertyId::Friction,
+                PropertyId::Resistance,
+            ]),
         ];
 
         for (node_type, properties) in test_matrix {
@@ -457,6 +657,6 @@ mod tests {
         assert_eq!(INPUT_COUNT, 2);
 
         const PROPERTY_COUNT: usize = NodeSchema::property_count(NodeType::Geometric);
-        assert_eq!(PROPERTY_COUNT, 7);
+        assert_eq!(PROPERTY_COUNT, 9);
     }
 }
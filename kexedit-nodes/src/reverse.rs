@@ -10,6 +10,7 @@ pub fn build(anchor: &Point) -> Point {
         anchor.energy,
         anchor.normal_force,
         -anchor.lateral_force,
+        -anchor.longitudinal_force,
         anchor.heart_arc,
         anchor.spine_arc,
         anchor.heart_advance,
@@ -17,6 +17,7 @@ pub fn build(path: &[Point]) -> Vec<Point> {
                 p.energy,
                 p.normal_force,
                 -p.lateral_force,
+                -p.longitudinal_force,
                 p.heart_arc,
                 p.spine_arc,
                 p.heart_advance,
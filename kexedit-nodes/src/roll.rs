@@ -0,0 +1,210 @@
+use kexedit_core::{evaluate, fit_keyframes, integrate, sim, Keyframe, Point};
+
+use crate::domain::{CurveDomain, Locator};
+use crate::PropertyId;
+
+const BANK_ITERATIONS: usize = 8;
+const BANK_TOLERANCE: f32 = 1e-4;
+
+/// How a section's roll keyframes are interpreted.
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RollChannel {
+    /// Roll rate in radians per unit of the section's keyframe parameter.
+    Speed = 0,
+    /// Absolute bank angle in radians, as reported by `Point::roll`.
+    Angle = 1,
+}
+
+impl RollChannel {
+    /// Property whose curve domain applies to the roll keys.
+    pub const fn property(self) -> PropertyId {
+        match self {
+            RollChannel::Speed => PropertyId::RollSpeed,
+            RollChannel::Angle => PropertyId::Roll,
+        }
+    }
+}
+
+/// Roll that brings `unrolled`, a step built without roll, to the bank `target`.
+pub(crate) fn roll_to_angle(unrolled: &Point, target: f32) -> f32 {
+    sim::wrap_angle(target - unrolled.roll())
+}
+
+/// Solves the per-step roll that makes `step(delta_roll)` produce `target_lateral` G.
+///
+/// Each iteration rolls by the angle between the current and the desired force
+/// direction in the plane of the cross-section, which converges in a few steps
+/// because rolling barely changes the force vector itself.
+pub(crate) fn solve_bank(target_lateral: f32, mut step: impl FnMut(f32) -> Point) -> (f32, Point) {
+    let mut delta_roll = 0.0;
+    let mut curr = step(delta_roll);
+
+    for _ in 0..BANK_ITERATIONS {
+        let error = curr.lateral_force - target_lateral;
+        if error.abs() < BANK_TOLERANCE {
+            break;
+        }
+
+        let magnitude_sq = curr.normal_force * curr.normal_force + curr.lateral_force * curr.lateral_force;
+        let target_normal = (magnitude_sq - target_lateral * target_lateral).max(0.0).sqrt();
+        let correction = curr.lateral_force.atan2(curr.normal_force) - target_lateral.atan2(target_normal);
+        delta_roll += correction;
+        curr = step(delta_roll);
+    }
+
+    (delta_roll, curr)
+}
+
+/// Fits keyframes to the roll speed recorded on each point of a built section,
+/// staying within `tolerance` of every point.
+///
+/// Each key sits where the builders evaluate `domain` for that step. Resolve
+/// `CurveDomain::Section` to the section's native domain first (spine distance
+/// for distance force sections, heart distance for geometric, angle for
+/// curved); left unresolved it is read as time. `points` must start with the
+/// section's anchor, as returned by the builders.
+pub fn roll_speed_keyframes(points: &[Point], domain: CurveDomain, tolerance: f32) -> Vec<Keyframe> {
+    if points.len() < 2 {
+        return Vec::new();
+    }
+
+    let mut locator = Locator::new(&points[0]);
+    let mut params = Vec::with_capacity(points.len() - 1);
+    for i in 1..points.len() {
+        let prev = &points[i - 1];
+        if i > 1 {
+            locator.advance(&points[i - 2], prev);
+        }
+        params.push(locator.locate(prev, i, i as f32 / sim::HZ).get(domain));
+    }
+    let values: Vec<f32> = points[1..].iter().map(|p| p.roll_speed).collect();
+
+    fit_keyframes(&params, &values, tolerance)
+}
+
+/// Integrates roll-speed keyframes into bank-angle keyframes starting at `start_angle`.
+///
+/// Both curves share the keyframe parameter, so this is exact for time and
+/// distance sections, where roll speed is per second or per metre.
+pub fn speed_to_angle(roll_speed: &[Keyframe], start_angle: f32, tolerance: f32) -> Vec<Keyframe> {
+    let params = sample_params(roll_speed);
+    let mut values = Vec::with_capacity(params.len());
+    let mut angle = start_angle;
+    for (i, &param) in params.iter().enumerate() {
+        if i > 0 {
+            angle += integrate(roll_speed, params[i - 1], param, 0.0);
+        }
+        values.push(angle);
+    }
+    fit_keyframes(&params, &values, tolerance)
+}
+
+/// Differentiates bank-angle keyframes into roll-speed keyframes.
+pub fn angle_to_speed(roll: &[Keyframe], tolerance: f32) -> Vec<Keyframe> {
+    let params = sample_params(roll);
+    let h = 0.5 / sim::HZ;
+    let values: Vec<f32> = params
+        .iter()
+        .map(|&param| (evaluate(roll, param + h, 0.0) - evaluate(roll, param - h, 0.0)) / (2.0 * h))
+        .collect();
+    fit_keyframes(&params, &values, tolerance)
+}
+
+/// Parameters from the first to the last key, `1 / sim::HZ` apart.
+fn sample_params(keyframes: &[Keyframe]) -> Vec<f32> {
+    let (Some(first), Some(last)) = (keyframes.first(), keyframes.last()) else {
+        return Vec::new();
+    };
+    let count = ((last.time - first.time) * sim::HZ).ceil().max(0.0) as usize;
+    (0..=count)
+        .map(|i| (first.time + i as f32 / sim::HZ).min(last.time))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn with_roll_speed(values: &[f32]) -> Vec<Point> {
+        let mut points = vec![Point::DEFAULT];
+        for &value in values {
+            let mut point = Point::DEFAULT;
+            point.roll_speed = value;
+            points.push(point);
+        }
+        points
+    }
+
+    #[test]
+    fn keyframes_reduce_linear_ramp_to_endpoints() {
+        let values: Vec<f32> = (1..=100).map(|i| i as f32 * 0.01).collect();
+        let points = with_roll_speed(&values);
+
+        let keys = roll_speed_keyframes(&points, CurveDomain::Time, 1e-4);
+
+        assert_eq!(keys.len(), 2);
+        assert_relative_eq!(keys[0].time, 0.01);
+        assert_relative_eq!(keys[1].time, 1.0);
+        assert_relative_eq!(keys[1].value, 1.0);
+    }
+
+    #[test]
+    fn keyframes_reproduce_recorded_roll_speed() {
+        let values: Vec<f32> = (1..=200).map(|i| (i as f32 * 0.01).sin()).collect();
+        let points = with_roll_speed(&values);
+
+        let keys = roll_speed_keyframes(&points, CurveDomain::Time, 1e-3);
+
+        assert!(keys.len() < values.len() / 2);
+        for (i, &value) in values.iter().enumerate() {
+            let t = (i + 1) as f32 / sim::HZ;
+            assert_relative_eq!(evaluate(&keys, t, 0.0), value, epsilon = 2e-3);
+        }
+    }
+
+    #[test]
+    fn keyframes_in_distance_sit_one_step_ahead() {
+        let mut points = with_roll_speed(&[0.0, 1.0]);
+        for (i, point) in points.iter_mut().enumerate() {
+            point.velocity = 10.0;
+            point.heart_arc = 5.0 + i as f32 * 10.0 / sim::HZ;
+        }
+
+        let keys = roll_speed_keyframes(&points, CurveDomain::HeartDistance, 1e-4);
+
+        assert_eq!(keys.len(), 2);
+        assert_relative_eq!(keys[0].time, 10.0 / sim::HZ);
+        assert_relative_eq!(keys[1].time, 20.0 / sim::HZ);
+    }
+
+    #[test]
+    fn keyframes_from_anchor_only_are_empty() {
+        assert!(roll_speed_keyframes(&[Point::DEFAULT], CurveDomain::Time, 1e-3).is_empty());
+    }
+
+    #[test]
+    fn speed_to_angle_integrates_constant_speed() {
+        let speed = [Keyframe::simple(0.0, 0.5), Keyframe::simple(2.0, 0.5)];
+
+        let angle = speed_to_angle(&speed, 0.1, 1e-4);
+
+        assert_eq!(angle.len(), 2);
+        assert_relative_eq!(angle[0].value, 0.1);
+        assert_relative_eq!(angle[1].value, 1.1, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn angle_and_speed_conversions_round_trip() {
+        let roll = [Keyframe::simple(0.0, 0.0), Keyframe::simple(3.0, 1.2)];
+
+        let speed = angle_to_speed(&roll, 1e-4);
+        let back = speed_to_angle(&speed, 0.0, 1e-4);
+
+        for i in 0..=30 {
+            let t = i as f32 * 0.1;
+            assert_relative_eq!(evaluate(&back, t, 0.0), evaluate(&roll, t, 0.0), epsilon = 5e-3);
+        }
+    }
+}
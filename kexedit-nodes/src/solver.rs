@@ -0,0 +1,461 @@
+use std::fmt;
+
+use kexedit_core::{sim, Keyframe, Point};
+
+use crate::force;
+use crate::roll::RollChannel;
+use crate::{DurationType, IterationConfig};
+
+const MIN_DURATION: f32 = 1e-3;
+const MAX_DAMPING: f32 = 1e8;
+
+/// Inputs to `force::build`, owned so the solver can rebuild with modified keyframes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForceSection {
+    pub anchor: Point,
+    pub config: IterationConfig,
+    pub driven: bool,
+    pub roll_channel: RollChannel,
+    pub roll: Vec<Keyframe>,
+    pub normal_force: Vec<Keyframe>,
+    pub lateral_force: Vec<Keyframe>,
+    pub driven_velocity: Vec<Keyframe>,
+    pub heart_offset: Vec<Keyframe>,
+    pub friction: Vec<Keyframe>,
+    pub resistance: Vec<Keyframe>,
+    pub anchor_heart: f32,
+    pub anchor_friction: f32,
+    pub anchor_resistance: f32,
+}
+
+impl ForceSection {
+    /// A section with no keyframes, which flies straight at 1 G.
+    pub fn new(anchor: Point, config: IterationConfig) -> Self {
+        Self {
+            anchor,
+            config,
+            driven: false,
+            roll_channel: RollChannel::Speed,
+            roll: Vec::new(),
+            normal_force: Vec::new(),
+            lateral_force: Vec::new(),
+            driven_velocity: Vec::new(),
+            heart_offset: Vec::new(),
+            friction: Vec::new(),
+            resistance: Vec::new(),
+            anchor_heart: anchor.heart_offset,
+            anchor_friction: anchor.friction,
+            anchor_resistance: anchor.resistance,
+        }
+    }
+
+    pub fn build(&self) -> Vec<Point> {
+        force::build(
+            &self.anchor,
+            &self.config,
+            self.driven,
+            self.roll_channel,
+            &self.roll,
+            &self.normal_force,
+            &self.lateral_force,
+            &self.driven_velocity,
+            &self.heart_offset,
+            &self.friction,
+            &self.resistance,
+            self.anchor_heart,
+            self.anchor_friction,
+            self.anchor_resistance,
+        )
+    }
+
+    /// Duration covered by one simulation step at the end of the section. Builds
+    /// stop on whole steps, so smaller duration changes have no effect.
+    fn sample_spacing(&self, points: &[Point]) -> f32 {
+        match self.config.duration_type {
+            DurationType::Time => 1.0 / sim::HZ,
+            DurationType::Distance => points.last().map_or(self.anchor.velocity, |p| p.velocity) / sim::HZ,
+        }
+    }
+
+    fn channel(&self, channel: ForceChannel) -> &[Keyframe] {
+        match channel {
+            ForceChannel::Roll => &self.roll,
+            ForceChannel::NormalForce => &self.normal_force,
+            ForceChannel::LateralForce => &self.lateral_force,
+        }
+    }
+
+    fn get(&self, variable: Variable) -> f32 {
+        match variable {
+            Variable::Value { channel, index } => self.channel(channel)[index].value,
+            Variable::Duration => self.config.duration,
+        }
+    }
+
+    fn set(&mut self, variable: Variable, value: f32) {
+        match variable {
+            Variable::Value { channel, index } => {
+                let keyframes = match channel {
+                    ForceChannel::Roll => &mut self.roll,
+                    ForceChannel::NormalForce => &mut self.normal_force,
+                    ForceChannel::LateralForce => &mut self.lateral_force,
+                };
+                keyframes[index].value = value;
+            }
+            Variable::Duration => self.config.duration = value.max(MIN_DURATION),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ForceChannel {
+    Roll,
+    NormalForce,
+    LateralForce,
+}
+
+/// A quantity the solver may change.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Variable {
+    /// The value of one keyframe; its time and tangents are left alone.
+    Value { channel: ForceChannel, index: usize },
+    /// The section duration, in the units of its `DurationType`.
+    Duration,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TargetKind {
+    /// Radians.
+    Pitch,
+    /// Radians.
+    Yaw,
+    /// Radians.
+    Roll,
+    /// Heart-line height in metres.
+    Height,
+    Velocity,
+}
+
+/// A condition on the last point of the section.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Target {
+    pub kind: TargetKind,
+    pub value: f32,
+    pub tolerance: f32,
+}
+
+impl Target {
+    pub const fn new(kind: TargetKind, value: f32, tolerance: f32) -> Self {
+        Self { kind, value, tolerance }
+    }
+
+    /// Signed miss, with angles wrapped to `[-π, π]`.
+    pub fn residual(&self, point: &Point) -> f32 {
+        let frame = point.frame();
+        match self.kind {
+            TargetKind::Pitch => sim::wrap_angle(frame.pitch() - self.value),
+            TargetKind::Yaw => sim::wrap_angle(frame.yaw() - self.value),
+            TargetKind::Roll => sim::wrap_angle(point.roll() - self.value),
+            TargetKind::Height => point.heart_position.y - self.value,
+            TargetKind::Velocity => point.velocity - self.value,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SolverConfig {
+    pub max_iterations: usize,
+    /// Finite-difference step for the Jacobian, relative to each variable's magnitude.
+    pub step: f32,
+}
+
+impl SolverConfig {
+    pub const DEFAULT: Self = Self::new(50, 1e-3);
+
+    pub const fn new(max_iterations: usize, step: f32) -> Self {
+        Self { max_iterations, step }
+    }
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolveError {
+    NoVariables,
+    NoTargets,
+    MissingKeyframe { channel: ForceChannel, index: usize },
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoVariables => write!(f, "no variables to solve for"),
+            Self::NoTargets => write!(f, "no targets to solve towards"),
+            Self::MissingKeyframe { channel, index } => {
+                write!(f, "{:?} has no keyframe at index {}", channel, index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solution {
+    /// The input section with the solved variables applied.
+    pub section: ForceSection,
+    pub points: Vec<Point>,
+    /// Every target is within its tolerance.
+    pub converged: bool,
+    pub iterations: usize,
+    /// Final residual per target, in target order.
+    pub residuals: Vec<f32>,
+}
+
+/// Residuals scaled by tolerance, so the section converges once every entry is within ±1.
+fn scaled_residuals(points: &[Point], targets: &[Target]) -> Vec<f32> {
+    let Some(last) = points.last() else {
+        return vec![f32::INFINITY; targets.len()];
+    };
+    targets
+        .iter()
+        .map(|target| target.residual(last) / target.tolerance.max(sim::EPSILON))
+        .collect()
+}
+
+fn cost(residuals: &[f32]) -> f32 {
+    residuals.iter().map(|r| r * r).sum()
+}
+
+fn within_tolerance(residuals: &[f32]) -> bool {
+    residuals.iter().all(|r| r.abs() <= 1.0)
+}
+
+/// Solves `matrix * x = rhs` by Gaussian elimination with partial pivoting.
+fn solve_linear(mut matrix: Vec<Vec<f32>>, mut rhs: Vec<f32>) -> Option<Vec<f32>> {
+    let n = rhs.len();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&a, &b| matrix[a][col].abs().total_cmp(&matrix[b][col].abs()))?;
+        if matrix[pivot][col].abs() < f32::MIN_POSITIVE {
+            return None;
+        }
+        matrix.swap(col, pivot);
+        rhs.swap(col, pivot);
+        for row in col + 1..n {
+            let factor = matrix[row][col] / matrix[col][col];
+            let pivot_row = matrix[col].clone();
+            for (value, pivot_value) in matrix[row][col..].iter_mut().zip(&pivot_row[col..]) {
+                *value -= factor * pivot_value;
+            }
+            rhs[row] -= factor * rhs[col];
+        }
+    }
+
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let sum: f32 = (row + 1..n).map(|k| matrix[row][k] * x[k]).sum();
+        x[row] = (rhs[row] - sum) / matrix[row][row];
+    }
+    Some(x)
+}
+
+/// Adjusts `variables` until the last point of the rebuilt section meets every target.
+///
+/// Uses Levenberg-Marquardt on residuals scaled by each target's tolerance,
+/// with a forward-difference Jacobian that costs one rebuild per variable per
+/// iteration. When the targets cannot all be met the closest section found is
+/// returned with `converged` false.
+pub fn solve(
+    section: &ForceSection,
+    variables: &[Variable],
+    targets: &[Target],
+    config: &SolverConfig,
+) -> Result<Solution, SolveError> {
+    if variables.is_empty() {
+        return Err(SolveError::NoVariables);
+    }
+    if targets.is_empty() {
+        return Err(SolveError::NoTargets);
+    }
+    for &variable in variables {
+        if let Variable::Value { channel, index } = variable {
+            if index >= section.channel(channel).len() {
+                return Err(SolveError::MissingKeyframe { channel, index });
+            }
+        }
+    }
+
+    let mut current = section.clone();
+    let mut points = current.build();
+    let mut residuals = scaled_residuals(&points, targets);
+    let mut damping = 1e-3;
+    let mut iterations = 0;
+
+    while iterations < config.max_iterations && !within_tolerance(&residuals) {
+        iterations += 1;
+
+        let values: Vec<f32> = variables.iter().map(|&v| current.get(v)).collect();
+        let mut jacobian = vec![vec![0.0; variables.len()]; targets.len()];
+        for (j, &variable) in variables.iter().enumerate() {
+            let h = match variable {
+                Variable::Value { .. } => config.step * values[j].abs().max(1.0),
+                Variable::Duration => current.sample_spacing(&points),
+            };
+            let mut probe = current.clone();
+            probe.set(variable, values[j] + h);
+            let h = probe.get(variable) - values[j];
+            if h.abs() < f32::EPSILON {
+                continue;
+            }
+            let probed = scaled_residuals(&probe.build(), targets);
+            for (i, row) in jacobian.iter_mut().enumerate() {
+                row[j] = (probed[i] - residuals[i]) / h;
+            }
+        }
+
+        let n = variables.len();
+        let mut normal = vec![vec![0.0; n]; n];
+        let mut gradient = vec![0.0; n];
+        for (i, row) in jacobian.iter().enumerate() {
+            for a in 0..n {
+                gradient[a] -= row[a] * residuals[i];
+                for b in 0..n {
+                    normal[a][b] += row[a] * row[b];
+                }
+            }
+        }
+
+        let mut improved = false;
+        while damping < MAX_DAMPING {
+            let mut damped = normal.clone();
+            for (a, row) in damped.iter_mut().enumerate() {
+                row[a] += damping * row[a].max(1e-6);
+            }
+            let Some(delta) = solve_linear(damped, gradient.clone()) else {
+                damping *= 10.0;
+                continue;
+            };
+
+            let mut trial = current.clone();
+            for (j, &variable) in variables.iter().enumerate() {
+                trial.set(variable, values[j] + delta[j]);
+            }
+            let trial_points = trial.build();
+            let trial_residuals = scaled_residuals(&trial_points, targets);
+            if cost(&trial_residuals) < cost(&residuals) {
+                current = trial;
+                points = trial_points;
+                residuals = trial_residuals;
+                damping = (damping * 0.1).max(1e-7);
+                improved = true;
+                break;
+            }
+            damping *= 10.0;
+        }
+
+        if !improved {
+            break;
+        }
+    }
+
+    let converged = within_tolerance(&residuals);
+    let residuals = match points.last() {
+        Some(last) => targets.iter().map(|t| t.residual(last)).collect(),
+        None => vec![f32::INFINITY; targets.len()],
+    };
+
+    Ok(Solution {
+        section: current,
+        points,
+        converged,
+        iterations,
+        residuals,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::anchor_at;
+
+    fn section(peak: f32, duration: f32) -> ForceSection {
+        let anchor = anchor_at(10.0, 20.0);
+        let mut section = ForceSection::new(anchor, IterationConfig::new(duration, DurationType::Time));
+        section.normal_force = vec![
+            Keyframe::simple(0.0, 1.0),
+            Keyframe::simple(1.0, peak),
+            Keyframe::simple(3.0, 1.0),
+        ];
+        section
+    }
+
+    const PEAK: Variable = Variable::Value {
+        channel: ForceChannel::NormalForce,
+        index: 1,
+    };
+
+    #[test]
+    fn solves_keyframe_value_for_end_pitch() {
+        let target = Target::new(TargetKind::Pitch, 20f32.to_radians(), 1e-3);
+
+        let solution = solve(&section(1.5, 2.0), &[PEAK], &[target], &SolverConfig::DEFAULT).unwrap();
+
+        assert!(solution.converged);
+        let last = solution.points.last().unwrap();
+        assert!((last.frame().pitch() - target.value).abs() <= target.tolerance);
+        assert!(solution.section.normal_force[1].value > 1.0);
+        assert_eq!(solution.section.normal_force[0], section(1.5, 2.0).normal_force[0]);
+    }
+
+    #[test]
+    fn solves_value_and_duration_for_pitch_and_height() {
+        let reference = section(1.8, 2.5).build();
+        let end = reference.last().unwrap();
+        let targets = [
+            Target::new(TargetKind::Pitch, end.frame().pitch(), 1e-3),
+            Target::new(TargetKind::Height, end.heart_position.y, 1e-2),
+        ];
+
+        let solution = solve(
+            &section(1.4, 2.0),
+            &[PEAK, Variable::Duration],
+            &targets,
+            &SolverConfig::DEFAULT,
+        )
+        .unwrap();
+
+        assert!(solution.converged, "residuals {:?}", solution.residuals);
+        assert!(solution.residuals[1].abs() <= 1e-2);
+    }
+
+    #[test]
+    fn unreachable_target_reports_no_convergence() {
+        let target = Target::new(TargetKind::Velocity, 100.0, 1e-2);
+
+        let solution = solve(&section(1.5, 2.0), &[PEAK], &[target], &SolverConfig::new(10, 1e-3)).unwrap();
+
+        assert!(!solution.converged);
+        assert!(solution.residuals[0] < 0.0);
+    }
+
+    #[test]
+    fn rejects_missing_keyframe() {
+        let variable = Variable::Value {
+            channel: ForceChannel::Roll,
+            index: 0,
+        };
+        let target = Target::new(TargetKind::Roll, 0.5, 1e-3);
+
+        assert_eq!(
+            solve(&section(1.5, 2.0), &[variable], &[target], &SolverConfig::DEFAULT),
+            Err(SolveError::MissingKeyframe {
+                channel: ForceChannel::Roll,
+                index: 0
+            })
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use kexedit_core::{sim, Curvature, Forces, Frame, Point};
+
+use crate::anchor::from_euler;
+
+const MAX_ITERATIONS: usize = 1_000_000;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct StraightParams {
+    /// Heartline length in metres.
+    pub length: f32,
+    /// Incline in degrees; negative descends.
+    pub pitch: f32,
+    /// Bank in degrees, positive rolling like a positive roll speed.
+    pub bank: f32,
+}
+
+impl StraightParams {
+    pub const fn new(length: f32, pitch: f32, bank: f32) -> Self {
+        Self { length, pitch, bank }
+    }
+}
+
+/// Straight track at a constant pitch and bank, for stations, lift hills and
+/// brake runs. The track keeps the anchor's heading and steps along a fixed
+/// frame, the last step shortened so the section ends at exactly `length`.
+/// An anchor at another pitch is turned to the section's frame in the first
+/// step, which shows as a force spike there, so join it through a transition
+/// where that matters.
+pub struct StraightNode;
+
+impl StraightNode {
+    /// With `driven_velocity` the train is held at that speed, as on a lift or
+    /// station tyres; otherwise it runs free and the section ends early if it
+    /// stalls. Heart offset, friction and resistance are taken from the anchor.
+    pub fn build(anchor: &Point, params: &StraightParams, driven_velocity: Option<f32>) -> Vec<Point> {
+        let (direction, normal, lateral) =
+            from_euler(params.pitch.to_radians(), anchor.frame().yaw(), params.bank.to_radians());
+        let frame = Frame::new(direction, normal, lateral);
+        let heart_offset = anchor.heart_offset;
+
+        let mut result = Vec::new();
+        result.push(*anchor);
+
+        let mut state = *anchor;
+        let mut distance = 0.0;
+
+        while distance < params.length {
+            if result.len() > MAX_ITERATIONS {
+                break;
+            }
+
+            let mut prev = state;
+            if let Some(velocity) = driven_velocity {
+                if velocity < sim::MIN_VELOCITY {
+                    break;
+                }
+                prev = prev.with_velocity(velocity, heart_offset, anchor.friction, true);
+            } else if prev.velocity < sim::MIN_VELOCITY {
+                if direction.y < 0.0 {
+                    prev = prev.with_velocity(sim::MIN_VELOCITY, heart_offset, anchor.friction, true);
+                } else {
+                    break;
+                }
+            }
+
+            let step_length = (prev.velocity / sim::HZ).min(params.length - distance);
+            let heart_position = prev.heart_position + direction * step_length;
+            let spine_advance =
+                (frame.spine_position(heart_position, heart_offset) - prev.spine_position(heart_offset)).magnitude();
+            let heart_arc = prev.heart_arc + step_length;
+
+            let (energy, velocity) = if driven_velocity.is_some() {
+                (prev.energy, prev.velocity)
+            } else {
+                let center_y = frame.spine_position(heart_position, 0.9 * heart_offset).y;
+                sim::update_energy(
+                    prev.energy,
+                    prev.velocity,
+                    center_y,
+                    heart_arc - prev.friction_origin,
+                    anchor.friction,
+                    anchor.resistance,
+                )
+            };
+
+            let forces = Forces::compute(Curvature::from_frames(frame, prev.frame()), frame, velocity, step_length);
+
+            let curr = Point::new(
+                heart_position,
+                direction,
+                normal,
+                lateral,
+                velocity,
+                energy,
+                forces.normal,
+                forces.lateral,
+                sim::longitudinal_force(state.velocity, velocity),
+                heart_arc,
+                prev.spine_arc + spine_advance,
+                step_length,
+                prev.friction_origin,
+                0.0,
+                heart_offset,
+                anchor.friction,
+                anchor.resistance,
+            );
+            result.push(curr);
+            state = curr;
+            distance += step_length;
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::anchor_at;
+    use approx::assert_relative_eq;
+    use kexedit_core::Float3;
+
+    #[test]
+    fn section_ends_at_exact_length() {
+        let result = StraightNode::build(&anchor_at(5.0, 10.0), &StraightParams::new(12.345, 0.0, 0.0), None);
+        let last = result.last().unwrap();
+
+        assert_relative_eq!(last.heart_arc, 12.345, epsilon = 1e-4);
+        assert_relative_eq!(last.heart_position.z, -12.345, epsilon = 1e-4);
+        assert_relative_eq!(last.velocity, 10.0, epsilon = 1e-3);
+        assert!(result[1..].iter().all(|point| (point.normal_force - 1.0).abs() < 1e-4));
+    }
+
+    #[test]
+    fn driven_lift_climbs_at_constant_speed() {
+        let direction = Frame::DEFAULT.with_pitch(30f32.to_radians()).direction;
+        let anchor = Point::create(Float3::new(0.0, 5.0, 0.0), direction, 0.0, 4.0, 1.1, 0.0, 0.0)
+            .with_velocity(4.0, 1.1, 0.0, true);
+        let result = StraightNode::build(&anchor, &StraightParams::new(40.0, 30.0, 0.0), Some(4.0));
+        let last = result.last().unwrap();
+
+        assert_relative_eq!(last.heart_position.y - 5.0, 20.0, epsilon = 1e-3);
+        assert_relative_eq!(last.frame().pitch(), 30f32.to_radians(), epsilon = 1e-4);
+        assert!(result[1..].iter().all(|point| point.velocity == 4.0));
+        assert!(result[1..].iter().all(|point| (point.normal_force - 30f32.to_radians().cos()).abs() < 1e-4));
+    }
+
+    #[test]
+    fn pitched_from_a_level_anchor_reports_the_kink() {
+        let result = StraightNode::build(&anchor_at(5.0, 10.0), &StraightParams::new(40.0, 30.0, 0.0), Some(4.0));
+
+        assert!(result[1].normal_force > 5.0, "normal force {}", result[1].normal_force);
+        assert!(result[2..].iter().all(|point| (point.normal_force - 30f32.to_radians().cos()).abs() < 1e-4));
+    }
+
+    #[test]
+    fn bank_rolls_the_track() {
+        let result = StraightNode::build(&anchor_at(5.0, 10.0), &StraightParams::new(10.0, 0.0, 20.0), Some(10.0));
+        let last = result.last().unwrap();
+
+        assert_relative_eq!(last.roll(), 20f32.to_radians(), epsilon = 1e-4);
+        assert_relative_eq!(last.lateral_force.abs(), 20f32.to_radians().sin(), epsilon = 1e-4);
+    }
+
+    #[test]
+    fn friction_slows_a_free_running_train() {
+        let anchor = Point::create(Float3::new(0.0, 5.0, 0.0), Float3::BACK, 0.0, 10.0, 1.1, 0.05, 0.0)
+            .with_velocity(10.0, 1.1, 0.05, true);
+        let result = StraightNode::build(&anchor, &StraightParams::new(30.0, 0.0, 0.0), None);
+
+        assert!(result.last().unwrap().velocity < 9.0);
+    }
+}
@@ -0,0 +1,267 @@
+use kexedit_core::{sim, Keyframe, PhysicsParams, Point};
+
+use crate::domain::{Locator, PropertyCurve, PropertyDomains};
+use crate::force::advance;
+use crate::PropertyId;
+
+const MAX_ITERATIONS: usize = 1_000_000;
+const SOLVE_ITERATIONS: usize = 8;
+const RADIUS_TOLERANCE: f32 = 1e-3;
+const HEIGHT_TOLERANCE: f32 = 1e-3;
+const SCALE_PROBE: f32 = 0.01;
+/// Below -1 G the track at the top would turn away from the loop's centre.
+const MIN_TOP_NORMAL_FORCE: f32 = -0.9;
+
+/// What the loop is shaped to achieve at its top.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum LoopTop {
+    /// Normal force in G at the top, where the train is inverted.
+    NormalForce(f32),
+    /// Heartline radius in metres at the top.
+    Radius(f32),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LoopParams {
+    /// Normal force in G at the bottom of the loop, its maximum.
+    pub max_normal_force: f32,
+    pub top: LoopTop,
+}
+
+impl LoopParams {
+    pub const fn new(max_normal_force: f32, top: LoopTop) -> Self {
+        Self { max_normal_force, top }
+    }
+
+    /// Target normal force `angle` degrees into the loop, easing from the
+    /// maximum at the bottom to `top_normal_force` at the top and back.
+    pub fn normal_force_at(&self, angle: f32, top_normal_force: f32) -> f32 {
+        let blend = 0.5 * (1.0 + angle.to_radians().cos());
+        top_normal_force + (self.max_normal_force - top_normal_force) * blend
+    }
+}
+
+/// Vertical loop built by force-vector stepping, pitching up through a full
+/// turn. The normal force profile is symmetric about the top, giving the
+/// usual teardrop shape, and the descent is tightened or widened slightly so
+/// the loop ends at the entry height and heading despite friction and
+/// stepping error.
+pub struct LoopNode;
+
+impl LoopNode {
+    /// Entry speed is the anchor's velocity. Property curves are keyed by time
+    /// by default. The train is never driven; the loop ends early if it stalls.
+    #[allow(clippy::too_many_arguments)]
+    pub fn build(
+        anchor: &Point,
+        params: &LoopParams,
+        heart_offset: &[Keyframe],
+        friction: &[Keyframe],
+        resistance: &[Keyframe],
+        domains: &PropertyDomains,
+        anchor_heart: f32,
+        anchor_friction: f32,
+        anchor_resistance: f32,
+    ) -> Vec<Point> {
+        let build = |top_normal_force: f32, descent_scale: f32| {
+            build_loop(
+                anchor,
+                params,
+                top_normal_force,
+                descent_scale,
+                heart_offset,
+                friction,
+                resistance,
+                domains,
+                anchor_heart,
+                anchor_friction,
+                anchor_resistance,
+            )
+        };
+
+        let top_normal_force = match params.top {
+            LoopTop::NormalForce(top_normal_force) => top_normal_force,
+            // The top speed depends on how high the loop climbs, which depends
+            // on the top force, so search for the force whose top radius matches.
+            LoopTop::Radius(radius) => secant(1.0, 0.0, RADIUS_TOLERANCE, |top_normal_force| {
+                let top_normal_force = top_normal_force.max(MIN_TOP_NORMAL_FORCE);
+                let result = build(top_normal_force, 1.0);
+                let top = highest(&result);
+                // `advance` turns the spine at the target force, and the
+                // heartline runs inside it at the top.
+                let spine_radius = top.velocity * top.velocity / ((top_normal_force + 1.0) * sim::G);
+                spine_radius - top.heart_offset - radius
+            })
+            .max(MIN_TOP_NORMAL_FORCE),
+        };
+
+        let descent_scale = secant(1.0, 1.0 + SCALE_PROBE, HEIGHT_TOLERANCE, |descent_scale| {
+            let result = build(top_normal_force, descent_scale);
+            result[result.len() - 1].heart_position.y - anchor.heart_position.y
+        });
+        build(top_normal_force, descent_scale)
+    }
+}
+
+fn highest(points: &[Point]) -> &Point {
+    points
+        .iter()
+        .max_by(|a, b| a.heart_position.y.total_cmp(&b.heart_position.y))
+        .expect("a built loop contains its anchor")
+}
+
+/// Root of `f` by the secant method from `x0` and `x1`, or the best point tried.
+fn secant(mut x0: f32, mut x1: f32, tolerance: f32, mut f: impl FnMut(f32) -> f32) -> f32 {
+    let mut f0 = f(x0);
+    let mut best = (x0, f0.abs());
+    for _ in 0..SOLVE_ITERATIONS {
+        let f1 = f(x1);
+        if f1.abs() < best.1 {
+            best = (x1, f1.abs());
+        }
+        if f1.abs() < tolerance || (f1 - f0).abs() < sim::EPSILON {
+            break;
+        }
+        let x2 = x1 - f1 * (x1 - x0) / (f1 - f0);
+        (x0, f0, x1) = (x1, f1, x2);
+    }
+    best.0
+}
+
+#[allow(clippy::too_many_arguments)]
+fn build_loop(
+    anchor: &Point,
+    params: &LoopParams,
+    top_normal_force: f32,
+    descent_scale: f32,
+    heart_offset: &[Keyframe],
+    friction: &[Keyframe],
+    resistance: &[Keyframe],
+    domains: &PropertyDomains,
+    anchor_heart: f32,
+    anchor_friction: f32,
+    anchor_resistance: f32,
+) -> Vec<Point> {
+    let mut heart_offset = PropertyCurve::new(heart_offset, domains.get(PropertyId::HeartOffset));
+    let mut friction = PropertyCurve::new(friction, domains.get(PropertyId::Friction));
+    let mut resistance = PropertyCurve::new(resistance, domains.get(PropertyId::Resistance));
+    let mut locator = Locator::new(anchor);
+
+    let mut result = Vec::new();
+    result.push(*anchor);
+
+    let mut state = *anchor;
+    let mut prev_heart_offset = anchor_heart;
+    let mut prev_friction = anchor_friction;
+
+    loop {
+        let index = result.len();
+        if index > MAX_ITERATIONS {
+            break;
+        }
+
+        let mut prev = state;
+        let here = locator.locate(&prev, index, index as f32 / sim::HZ);
+        if here.angle >= 360.0 - sim::EPSILON {
+            break;
+        }
+
+        if prev.velocity < sim::MIN_VELOCITY {
+            if prev.frame().pitch() < 0.0 {
+                prev = prev.with_velocity(sim::MIN_VELOCITY, prev_heart_offset, prev_friction, true);
+            } else {
+                break;
+            }
+        }
+
+        let heart_offset_val = heart_offset.evaluate(&here, anchor_heart);
+        let friction_val = friction.evaluate(&here, anchor_friction);
+        let resistance_val = resistance.evaluate(&here, anchor_resistance);
+
+        let mut target_normal_force = params.normal_force_at(here.angle, top_normal_force);
+        if here.angle > 180.0 {
+            // Scale the part of the force that turns the track, not gravity's.
+            let gravity = prev.normal.y;
+            target_normal_force = (target_normal_force + gravity) * descent_scale - gravity;
+        }
+        let physics = PhysicsParams::new(heart_offset_val, friction_val, resistance_val, 0.0, false);
+        let curr = advance(&prev, target_normal_force, 0.0, &physics, 0.0, state.velocity);
+
+        result.push(curr);
+        locator.advance(&state, &curr);
+        state = curr;
+        prev_heart_offset = heart_offset_val;
+        prev_friction = friction_val;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::anchor_at;
+    use approx::assert_relative_eq;
+    use kexedit_core::Float3;
+
+    fn build(params: &LoopParams) -> Vec<Point> {
+        LoopNode::build(&anchor_at(3.0, 20.0), params, &[], &[], &[], &PropertyDomains::DEFAULT, 1.1, 0.0, 0.0)
+    }
+
+    fn top(result: &[Point]) -> usize {
+        (0..result.len())
+            .max_by(|&a, &b| result[a].heart_position.y.total_cmp(&result[b].heart_position.y))
+            .unwrap()
+    }
+
+    #[test]
+    fn loop_returns_to_entry_height_and_heading() {
+        let result = build(&LoopParams::new(4.0, LoopTop::NormalForce(1.0)));
+        let first = &result[0];
+        let last = result.last().unwrap();
+
+        assert!(result[top(&result)].heart_position.y > first.heart_position.y + 8.0);
+        assert_relative_eq!(last.heart_position.y, first.heart_position.y, epsilon = 0.01);
+        assert!(last.direction.dot(first.direction) > 0.999);
+        assert_relative_eq!(last.velocity, first.velocity, epsilon = 0.1);
+    }
+
+    #[test]
+    fn normal_force_peaks_at_bottom_and_eases_to_top() {
+        let anchor = Point::create(Float3::new(0.0, 3.0, 0.0), Float3::BACK, 0.0, 20.0, 0.0, 0.0, 0.0);
+        let params = LoopParams::new(4.0, LoopTop::NormalForce(0.5));
+
+        let result = LoopNode::build(&anchor, &params, &[], &[], &[], &PropertyDomains::DEFAULT, 0.0, 0.0, 0.0);
+
+        assert_relative_eq!(result[2].normal_force, 4.0, epsilon = 0.01);
+        assert_relative_eq!(result[top(&result)].normal_force, 0.5, epsilon = 0.01);
+        assert_relative_eq!(params.normal_force_at(90.0, 0.5), 2.25);
+    }
+
+    #[test]
+    fn top_radius_is_met() {
+        let result = build(&LoopParams::new(4.0, LoopTop::Radius(3.0)));
+        let i = top(&result);
+        let (a, b, c) = (result[i - 3].heart_position, result[i].heart_position, result[i + 3].heart_position);
+
+        let (ab, bc, ca) = ((b - a).magnitude(), (c - b).magnitude(), (a - c).magnitude());
+        let radius = ab * bc * ca / (2.0 * (b - a).cross(c - a).magnitude());
+
+        assert_relative_eq!(radius, 3.0, epsilon = 0.05);
+        assert_relative_eq!(result.last().unwrap().heart_position.y, 3.0, epsilon = 0.01);
+    }
+
+    #[test]
+    fn friction_is_absorbed_by_the_descent() {
+        let anchor = Point::create(Float3::new(0.0, 3.0, 0.0), Float3::BACK, 0.0, 20.0, 1.1, 0.03, 0.0);
+
+        let result = LoopNode::build(
+            &anchor, &LoopParams::new(4.0, LoopTop::NormalForce(1.0)), &[], &[], &[], &PropertyDomains::DEFAULT, 1.1, 0.03, 0.0,
+        );
+        let last = result.last().unwrap();
+
+        assert!(last.velocity < 19.5);
+        assert_relative_eq!(last.heart_position.y, 3.0, epsilon = 0.01);
+        assert!(last.direction.dot(anchor.direction) > 0.999);
+    }
+}